@@ -0,0 +1,124 @@
+use proc_macro2::{Ident, TokenStream, TokenTree};
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::{Attribute, Expr, Lit, LitByteStr, LitStr};
+
+/// Attrs is the `#[bin(...)]` vocabulary shared by the derives.
+#[derive(Default)]
+pub(crate) struct Attrs {
+  pub endian: Option<TokenStream>,
+  pub magic: Option<LitByteStr>,
+  pub count: Option<Expr>,
+  pub cond: Option<Expr>,
+  pub pad_before: Option<Expr>,
+  pub pad_after: Option<Expr>,
+  pub seek_before: Option<Expr>,
+  pub align_before: Option<Expr>,
+  pub align_after: Option<Expr>,
+}
+
+impl Attrs {
+  pub(crate) fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+    let mut result = Attrs::default();
+    for attr in attrs {
+      if !attr.path().is_ident("bin") {
+        continue;
+      }
+      attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("endian") {
+          let s: LitStr = meta.value()?.parse()?;
+          result.endian = match s.value().as_str() {
+            "big" => Some(quote!(::bin_rs::Endian::BigEndian)),
+            "little" => Some(quote!(::bin_rs::Endian::LittleEndian)),
+            _ => return Err(meta.error("endian must be \"big\" or \"little\"")),
+          };
+        } else if meta.path.is_ident("magic") {
+          result.magic = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("count") {
+          result.count = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("if") {
+          result.cond = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("pad_before") {
+          result.pad_before = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("pad_after") {
+          result.pad_after = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("seek_before") {
+          result.seek_before = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("align_before") {
+          result.align_before = Some(parse_align(&meta)?);
+        } else if meta.path.is_ident("align_after") {
+          result.align_after = Some(parse_align(&meta)?);
+        } else {
+          return Err(meta.error("unknown bin attribute"));
+        }
+        Ok(())
+      })?;
+    }
+    Ok(result)
+  }
+
+  /// parse_struct accepts only the attributes for a whole struct.
+  pub(crate) fn parse_struct(attrs: &[Attribute]) -> syn::Result<Self> {
+    let result = Self::parse(attrs)?;
+    let field_only = result.count.is_some()
+      || result.cond.is_some()
+      || result.pad_before.is_some()
+      || result.pad_after.is_some()
+      || result.seek_before.is_some()
+      || result.align_before.is_some()
+      || result.align_after.is_some();
+    if field_only {
+      let span = attrs
+        .iter()
+        .find(|a| a.path().is_ident("bin"))
+        .map(|a| a.pound_token.span)
+        .unwrap_or_else(proc_macro2::Span::call_site);
+      return Err(syn::Error::new(
+        span,
+        "only endian and magic can be used for a struct",
+      ));
+    }
+    Ok(result)
+  }
+
+  /// exprs returns the expressions which can refer other fields.
+  pub(crate) fn exprs(&self) -> Vec<&Expr> {
+    [
+      &self.count,
+      &self.cond,
+      &self.pad_before,
+      &self.pad_after,
+      &self.seek_before,
+      &self.align_before,
+      &self.align_after,
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+  }
+}
+
+/// parse_align rejects a literal 0, which can not be a multiple.
+fn parse_align(meta: &ParseNestedMeta) -> syn::Result<Expr> {
+  let expr: Expr = meta.value()?.parse()?;
+  if let Expr::Lit(lit) = &expr {
+    if let Lit::Int(int) = &lit.lit {
+      if int.base10_parse::<u64>()? == 0 {
+        return Err(syn::Error::new_spanned(
+          &expr,
+          "align must be greater than 0",
+        ));
+      }
+    }
+  }
+  Ok(expr)
+}
+
+/// refers returns true if `tokens` contain `ident`.
+pub(crate) fn refers(tokens: TokenStream, ident: &Ident) -> bool {
+  tokens.into_iter().any(|token| match token {
+    TokenTree::Ident(i) => i == *ident,
+    TokenTree::Group(group) => refers(group.stream(), ident),
+    _ => false,
+  })
+}
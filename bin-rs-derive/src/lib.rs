@@ -0,0 +1,60 @@
+//! Derive macros for bin-rs, use feature="derive" of bin-rs.
+//!
+//! `#[derive(BinRead)]` implements `bin_rs::reader::FromBinary` for a struct,
+//! so it can be read by `reader.read::<T>()` or nested in another struct.
+//! `#[derive(BinWrite)]` implements `bin_rs::writer::ToBinary` with the same attributes,
+//! so it can be written by `writer.write_value(&value)`.
+//!
+//! Attributes are written as `#[bin(...)]`.
+//!
+//! | attribute | on | meaning |
+//! |---|---|---|
+//! | `endian = "big"` / `"little"` | struct, field | overrides the reader's endian |
+//! | `magic = b"..."` | struct, field | these bytes must come first |
+//! | `count = expr` | `Vec<T>` field | number of elements |
+//! | `if = expr` | `Option<T>` field | the field exists only if `expr` is true |
+//! | `pad_before = expr` / `pad_after = expr` | field | skips bytes |
+//! | `seek_before = expr` | field | seeks to `expr: SeekFrom` |
+//! | `align_before = expr` / `align_after = expr` | field | pads to a multiple of `expr` bytes, `expr` is not 0 |
+//!
+//! Expressions can use the fields read before.
+//! When writing, a field named by `count = field` is written from the length of the `Vec`,
+//! other `count` expressions and `if` are checked against the field value, and
+//! padding is written as 0x00.
+//! ```ignore
+//! use bin_rs::reader::*;
+//! use bin_rs::{BinRead, BinWrite};
+//!
+//! #[derive(BinRead, BinWrite)]
+//! #[bin(magic = b"BMP", endian = "little")]
+//! struct Header {
+//!   width: u32,
+//!   height: u32,
+//!   colors: u8,
+//!   #[bin(count = colors)]
+//!   palette: Vec<[u8; 3]>,
+//! }
+//! ```
+
+mod attr;
+mod read;
+mod write;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+#[proc_macro_derive(BinRead, attributes(bin))]
+pub fn derive_bin_read(input: TokenStream) -> TokenStream {
+  let input = parse_macro_input!(input as DeriveInput);
+  read::derive(input)
+    .unwrap_or_else(|e| e.to_compile_error())
+    .into()
+}
+
+#[proc_macro_derive(BinWrite, attributes(bin))]
+pub fn derive_bin_write(input: TokenStream) -> TokenStream {
+  let input = parse_macro_input!(input as DeriveInput);
+  write::derive(input)
+    .unwrap_or_else(|e| e.to_compile_error())
+    .into()
+}
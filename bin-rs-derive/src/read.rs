@@ -0,0 +1,140 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_quote, Data, DeriveInput, Fields, GenericParam};
+
+use crate::attr::Attrs;
+
+pub(crate) fn derive(input: DeriveInput) -> syn::Result<TokenStream> {
+  let data = match &input.data {
+    Data::Struct(data) => data,
+    _ => {
+      return Err(syn::Error::new_spanned(
+        &input.ident,
+        "BinRead can be derived only for a struct",
+      ))
+    }
+  };
+  let struct_attrs = Attrs::parse_struct(&input.attrs)?;
+  let struct_endian = match &struct_attrs.endian {
+    Some(endian) => endian.clone(),
+    None => quote!(__endian),
+  };
+
+  let mut body = Vec::new();
+  if let Some(magic) = &struct_attrs.magic {
+    body.push(read_magic(magic));
+  }
+
+  let mut names = Vec::new();
+  for (i, field) in data.fields.iter().enumerate() {
+    let attrs = Attrs::parse(&field.attrs)?;
+    let name = match &field.ident {
+      Some(ident) => ident.clone(),
+      None => format_ident!("__field{}", i),
+    };
+    let ty = &field.ty;
+    let endian = match &attrs.endian {
+      Some(endian) => endian.clone(),
+      None => struct_endian.clone(),
+    };
+
+    if let Some(seek) = &attrs.seek_before {
+      body.push(quote!(__reader.seek(#seek)?;));
+    }
+    if let Some(pad) = &attrs.pad_before {
+      body.push(quote!(__reader.skip_ptr((#pad) as usize)?;));
+    }
+    if let Some(align) = &attrs.align_before {
+      body.push(skip_align(align));
+    }
+    if let Some(magic) = &attrs.magic {
+      body.push(read_magic(magic));
+    }
+    let mut value = match &attrs.count {
+      Some(count) => quote!({
+        let __count = (#count) as usize;
+        let mut __array = ::std::vec::Vec::with_capacity(__count.min(4096));
+        for _ in 0..__count {
+          __array.push(::bin_rs::reader::FromBinary::read_from(__reader, #endian)?);
+        }
+        __array
+      }),
+      None => quote!(::bin_rs::reader::FromBinary::read_from(__reader, #endian)?),
+    };
+    if let Some(cond) = &attrs.cond {
+      value = quote!(if #cond { ::std::option::Option::Some(#value) } else { ::std::option::Option::None });
+    }
+    body.push(quote!(let #name: #ty = #value;));
+    if let Some(pad) = &attrs.pad_after {
+      body.push(quote!(__reader.skip_ptr((#pad) as usize)?;));
+    }
+    if let Some(align) = &attrs.align_after {
+      body.push(skip_align(align));
+    }
+    names.push(name);
+  }
+
+  let construct = match &data.fields {
+    Fields::Named(_) => quote!(Self { #(#names),* }),
+    Fields::Unnamed(_) => quote!(Self ( #(#names),* )),
+    Fields::Unit => quote!(Self),
+  };
+
+  let mut generics = input.generics.clone();
+  for param in generics.params.iter_mut() {
+    if let GenericParam::Type(param) = param {
+      param
+        .bounds
+        .push(parse_quote!(::bin_rs::reader::FromBinary));
+    }
+  }
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+  let ident = &input.ident;
+
+  Ok(quote! {
+    impl #impl_generics ::bin_rs::reader::FromBinary for #ident #ty_generics #where_clause {
+      #[allow(unused_variables, non_snake_case)]
+      fn read_from<__R: ::bin_rs::reader::BinaryReader + ?Sized>(
+        __reader: &mut __R,
+        __endian: ::bin_rs::Endian,
+      ) -> ::std::result::Result<Self, ::std::io::Error> {
+        #(#body)*
+        ::std::result::Result::Ok(#construct)
+      }
+    }
+  })
+}
+
+fn skip_align(align: &syn::Expr) -> TokenStream {
+  quote! {
+    {
+      let __align = (#align) as u64;
+      if __align == 0 {
+        let __message = ::std::string::String::from("align must be greater than 0");
+        return ::std::result::Result::Err(::bin_rs::error::BinError::InvalidInput(__message).into());
+      }
+      let __offset = __reader.offset()?;
+      __reader.skip_ptr(((__align - __offset % __align) % __align) as usize)?;
+    }
+  }
+}
+
+fn read_magic(magic: &syn::LitByteStr) -> TokenStream {
+  let len = magic.value().len();
+  quote! {
+    {
+      let __offset = __reader.offset()?;
+      let __magic = __reader.read_bytes_as_vec(#len)?;
+      if __magic.as_slice() != &#magic[..] {
+        return ::std::result::Result::Err(
+          ::bin_rs::error::BinError::BadMagic {
+            offset: __offset,
+            expected: #magic.to_vec(),
+            found: __magic,
+          }
+          .into(),
+        );
+      }
+    }
+  }
+}
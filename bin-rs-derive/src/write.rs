@@ -0,0 +1,217 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::{parse_quote, Data, DeriveInput, Expr, GenericParam, Ident, Index, Member};
+
+use crate::attr::{refers, Attrs};
+
+pub(crate) fn derive(input: DeriveInput) -> syn::Result<TokenStream> {
+  let data = match &input.data {
+    Data::Struct(data) => data,
+    _ => {
+      return Err(syn::Error::new_spanned(
+        &input.ident,
+        "BinWrite can be derived only for a struct",
+      ))
+    }
+  };
+  let struct_attrs = Attrs::parse_struct(&input.attrs)?;
+  let struct_endian = match &struct_attrs.endian {
+    Some(endian) => endian.clone(),
+    None => quote!(__endian),
+  };
+
+  let mut fields = Vec::new();
+  for (i, field) in data.fields.iter().enumerate() {
+    let attrs = Attrs::parse(&field.attrs)?;
+    let (name, member) = match &field.ident {
+      Some(ident) => (ident.clone(), Member::Named(ident.clone())),
+      None => (
+        format_ident!("__field{}", i),
+        Member::Unnamed(Index::from(i)),
+      ),
+    };
+    fields.push((name, member, attrs, &field.ty));
+  }
+
+  // a field named by `count = field` is written from the length of the Vec
+  let mut lengths: Vec<(&Ident, &Member)> = Vec::new();
+  for (_, member, attrs, _) in fields.iter() {
+    let counter = match (&attrs.count, &attrs.cond) {
+      (Some(Expr::Path(path)), None) => path.path.get_ident(),
+      _ => None,
+    };
+    if let Some(counter) = counter {
+      let plain = fields
+        .iter()
+        .any(|(name, _, attrs, _)| name == counter && attrs.cond.is_none());
+      if plain && lengths.iter().all(|(name, _)| *name != counter) {
+        lengths.push((counter, member));
+      }
+    }
+  }
+  let length_of = |name: &Ident| {
+    lengths
+      .iter()
+      .find(|(counter, _)| *counter == name)
+      .map(|(_, member)| *member)
+  };
+
+  let mut body = Vec::new();
+  // fields used by expressions are bound as locals, like BinRead
+  for (name, member, _, ty) in fields.iter() {
+    if let Some(vec) = length_of(name) {
+      let label = name.to_string();
+      body.push(quote! {
+        let #name: #ty = match ::std::convert::TryFrom::try_from(self.#vec.len()) {
+          ::std::result::Result::Ok(__len) => __len,
+          ::std::result::Result::Err(_) => {
+            return ::std::result::Result::Err(
+              ::bin_rs::error::BinError::Overflow {
+                offset: __writer.offset()?,
+                message: ::std::format!("{} elements do not fit in {}", self.#vec.len(), #label),
+              }
+              .into(),
+            );
+          }
+        };
+      });
+      continue;
+    }
+    let used = fields.iter().any(|(_, _, attrs, _)| {
+      attrs
+        .exprs()
+        .into_iter()
+        .any(|expr| refers(expr.to_token_stream(), name))
+    });
+    if used {
+      body.push(quote!(let #name = ::std::clone::Clone::clone(&self.#member);));
+    }
+  }
+
+  if let Some(magic) = &struct_attrs.magic {
+    body.push(quote!(__writer.write_bytes(&#magic[..])?;));
+  }
+
+  for (name, member, attrs, _) in fields.iter() {
+    let endian = match &attrs.endian {
+      Some(endian) => endian.clone(),
+      None => struct_endian.clone(),
+    };
+    let label = name.to_string();
+
+    if let Some(seek) = &attrs.seek_before {
+      body.push(quote!(__writer.seek(#seek)?;));
+    }
+    if let Some(pad) = &attrs.pad_before {
+      body.push(write_pad(pad));
+    }
+    if let Some(align) = &attrs.align_before {
+      body.push(write_align(align));
+    }
+    if let Some(magic) = &attrs.magic {
+      body.push(quote!(__writer.write_bytes(&#magic[..])?;));
+    }
+    let value = match &attrs.count {
+      Some(count) => quote! {
+        let __count = (#count) as usize;
+        if __value.len() != __count {
+          return ::std::result::Result::Err(
+            ::bin_rs::error::BinError::InvalidData {
+              offset: __writer.offset()?,
+              message: ::std::format!(
+                "{} has {} elements, but count is {}",
+                #label,
+                __value.len(),
+                __count
+              ),
+            }
+            .into(),
+          );
+        }
+        for __v in __value.iter() {
+          ::bin_rs::writer::ToBinary::write_to(__v, __writer, #endian)?;
+        }
+      },
+      None => quote!(::bin_rs::writer::ToBinary::write_to(__value, __writer, #endian)?;),
+    };
+    match &attrs.cond {
+      Some(cond) => body.push(quote! {
+        match (#cond, &self.#member) {
+          (true, ::std::option::Option::Some(__value)) => {
+            #value
+          }
+          (false, ::std::option::Option::None) => {}
+          _ => {
+            return ::std::result::Result::Err(
+              ::bin_rs::error::BinError::InvalidData {
+                offset: __writer.offset()?,
+                message: ::std::format!("{} does not match its condition", #label),
+              }
+              .into(),
+            );
+          }
+        }
+      }),
+      None => {
+        let field = match length_of(name) {
+          Some(_) => quote!(#name),
+          None => quote!(self.#member),
+        };
+        body.push(quote! {
+          {
+            let __value = &#field;
+            #value
+          }
+        })
+      }
+    }
+    if let Some(pad) = &attrs.pad_after {
+      body.push(write_pad(pad));
+    }
+    if let Some(align) = &attrs.align_after {
+      body.push(write_align(align));
+    }
+  }
+
+  let mut generics = input.generics.clone();
+  for param in generics.params.iter_mut() {
+    if let GenericParam::Type(param) = param {
+      param.bounds.push(parse_quote!(::bin_rs::writer::ToBinary));
+    }
+  }
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+  let ident = &input.ident;
+
+  Ok(quote! {
+    impl #impl_generics ::bin_rs::writer::ToBinary for #ident #ty_generics #where_clause {
+      #[allow(unused_variables, non_snake_case)]
+      fn write_to<__W: ::bin_rs::writer::BinaryWriter + ?Sized>(
+        &self,
+        __writer: &mut __W,
+        __endian: ::bin_rs::Endian,
+      ) -> ::std::result::Result<(), ::std::io::Error> {
+        #(#body)*
+        ::std::result::Result::Ok(())
+      }
+    }
+  })
+}
+
+fn write_pad(pad: &syn::Expr) -> TokenStream {
+  quote!(__writer.write_bytes(&::std::vec![0_u8; (#pad) as usize])?;)
+}
+
+fn write_align(align: &syn::Expr) -> TokenStream {
+  quote! {
+    {
+      let __align = (#align) as u64;
+      if __align == 0 {
+        let __message = ::std::string::String::from("align must be greater than 0");
+        return ::std::result::Result::Err(::bin_rs::error::BinError::InvalidInput(__message).into());
+      }
+      let __offset = __writer.offset()?;
+      let __rest = ((__align - __offset % __align) % __align) as usize;
+      __writer.write_bytes(&::std::vec![0_u8; __rest])?;
+    }
+  }
+}
@@ -0,0 +1,587 @@
+use crate::error::{BinError, Malformed};
+#[cfg(feature = "codec")]
+use crate::reader::DecodeOptions;
+use crate::reader::{check_supported, check_width, end_of_data, CodeType, LengthPrefix, Trim};
+use crate::varint::{decode_zigzag, Sleb128, Uleb128, Varint, Vlq};
+use crate::Endian;
+use std::future::Future;
+use std::io::{Error, SeekFrom};
+
+/// 0.0.11 AsyncBinaryReader is the async version of `BinaryReader`.
+///
+/// An implementation only has to provide `read_exact`, `read_bytes_no_move`,
+/// `skip_ptr`, `offset`, `seek` and endian handling, like `BinaryReader`.
+/// Every future is `Send`, so a generic parser can run in a spawned task.
+/// ```
+/// use bin_rs::async_reader::*;
+/// use std::io::Error;
+///
+/// async fn read_chunk<R: AsyncBinaryReader>(reader: &mut R) -> Result<(String, Vec<u8>), Error> {
+///   let id = reader.read_ascii_string(4).await?;
+///   let size = reader.read_u32_le().await? as usize;
+///   let data = reader.read_bytes_as_vec(size).await?;
+///   Ok((id, data))
+/// }
+/// ```
+pub trait AsyncBinaryReader: Send {
+  fn set_endian(&mut self, endian: Endian);
+  fn endian(&self) -> Endian;
+
+  fn read_byte(&mut self) -> impl Future<Output = Result<u8, Error>> + Send {
+    async move {
+      let mut array = [0; 1];
+      self.read_exact(&mut array).await?;
+      Ok(array[0])
+    }
+  }
+
+  fn read_u8(&mut self) -> impl Future<Output = Result<u8, Error>> + Send {
+    self.read_byte()
+  }
+
+  fn read_bytes(&mut self, array: &mut [u8]) -> impl Future<Output = Result<(), Error>> + Send {
+    self.read_exact(array)
+  }
+
+  fn read_exact(&mut self, array: &mut [u8]) -> impl Future<Output = Result<(), Error>> + Send;
+
+  fn read_bytes_as_vec(
+    &mut self,
+    len: usize,
+  ) -> impl Future<Output = Result<Vec<u8>, Error>> + Send {
+    async move {
+      let mut array = vec![0; len];
+      self.read_exact(&mut array).await?;
+      Ok(array)
+    }
+  }
+
+  /// read_bytes_no_move does not move offset after read_bytes.
+  ///
+  /// Assumed to be used for header checks.
+  ///
+  fn read_bytes_no_move(
+    &mut self,
+    len: usize,
+  ) -> impl Future<Output = Result<Vec<u8>, Error>> + Send;
+
+  fn read_u16(&mut self) -> impl Future<Output = Result<u16, Error>> + Send {
+    async move {
+      match self.endian() {
+        Endian::BigEndian => self.read_u16_be().await,
+        Endian::LittleEndian => self.read_u16_le().await,
+      }
+    }
+  }
+
+  fn read_u32(&mut self) -> impl Future<Output = Result<u32, Error>> + Send {
+    async move {
+      match self.endian() {
+        Endian::BigEndian => self.read_u32_be().await,
+        Endian::LittleEndian => self.read_u32_le().await,
+      }
+    }
+  }
+
+  fn read_u64(&mut self) -> impl Future<Output = Result<u64, Error>> + Send {
+    async move {
+      match self.endian() {
+        Endian::BigEndian => self.read_u64_be().await,
+        Endian::LittleEndian => self.read_u64_le().await,
+      }
+    }
+  }
+
+  fn read_u128(&mut self) -> impl Future<Output = Result<u128, Error>> + Send {
+    async move {
+      match self.endian() {
+        Endian::BigEndian => self.read_u128_be().await,
+        Endian::LittleEndian => self.read_u128_le().await,
+      }
+    }
+  }
+
+  fn read_i8(&mut self) -> impl Future<Output = Result<i8, Error>> + Send {
+    async move { Ok(self.read_byte().await? as i8) }
+  }
+
+  fn read_i16(&mut self) -> impl Future<Output = Result<i16, Error>> + Send {
+    async move {
+      match self.endian() {
+        Endian::BigEndian => self.read_i16_be().await,
+        Endian::LittleEndian => self.read_i16_le().await,
+      }
+    }
+  }
+
+  fn read_i32(&mut self) -> impl Future<Output = Result<i32, Error>> + Send {
+    async move {
+      match self.endian() {
+        Endian::BigEndian => self.read_i32_be().await,
+        Endian::LittleEndian => self.read_i32_le().await,
+      }
+    }
+  }
+
+  fn read_i64(&mut self) -> impl Future<Output = Result<i64, Error>> + Send {
+    async move {
+      match self.endian() {
+        Endian::BigEndian => self.read_i64_be().await,
+        Endian::LittleEndian => self.read_i64_le().await,
+      }
+    }
+  }
+
+  fn read_i128(&mut self) -> impl Future<Output = Result<i128, Error>> + Send {
+    async move {
+      match self.endian() {
+        Endian::BigEndian => self.read_i128_be().await,
+        Endian::LittleEndian => self.read_i128_le().await,
+      }
+    }
+  }
+
+  fn read_f32(&mut self) -> impl Future<Output = Result<f32, Error>> + Send {
+    async move {
+      match self.endian() {
+        Endian::BigEndian => self.read_f32_be().await,
+        Endian::LittleEndian => self.read_f32_le().await,
+      }
+    }
+  }
+
+  fn read_f64(&mut self) -> impl Future<Output = Result<f64, Error>> + Send {
+    async move {
+      match self.endian() {
+        Endian::BigEndian => self.read_f64_be().await,
+        Endian::LittleEndian => self.read_f64_le().await,
+      }
+    }
+  }
+
+  fn read_u16_be(&mut self) -> impl Future<Output = Result<u16, Error>> + Send {
+    async move {
+      let mut array = [0; 2];
+      self.read_exact(&mut array).await?;
+      Ok(u16::from_be_bytes(array))
+    }
+  }
+
+  fn read_u32_be(&mut self) -> impl Future<Output = Result<u32, Error>> + Send {
+    async move {
+      let mut array = [0; 4];
+      self.read_exact(&mut array).await?;
+      Ok(u32::from_be_bytes(array))
+    }
+  }
+
+  fn read_u64_be(&mut self) -> impl Future<Output = Result<u64, Error>> + Send {
+    async move {
+      let mut array = [0; 8];
+      self.read_exact(&mut array).await?;
+      Ok(u64::from_be_bytes(array))
+    }
+  }
+
+  fn read_u128_be(&mut self) -> impl Future<Output = Result<u128, Error>> + Send {
+    async move {
+      let mut array = [0; 16];
+      self.read_exact(&mut array).await?;
+      Ok(u128::from_be_bytes(array))
+    }
+  }
+
+  fn read_i16_be(&mut self) -> impl Future<Output = Result<i16, Error>> + Send {
+    async move {
+      let mut array = [0; 2];
+      self.read_exact(&mut array).await?;
+      Ok(i16::from_be_bytes(array))
+    }
+  }
+
+  fn read_i32_be(&mut self) -> impl Future<Output = Result<i32, Error>> + Send {
+    async move {
+      let mut array = [0; 4];
+      self.read_exact(&mut array).await?;
+      Ok(i32::from_be_bytes(array))
+    }
+  }
+
+  fn read_i64_be(&mut self) -> impl Future<Output = Result<i64, Error>> + Send {
+    async move {
+      let mut array = [0; 8];
+      self.read_exact(&mut array).await?;
+      Ok(i64::from_be_bytes(array))
+    }
+  }
+
+  fn read_i128_be(&mut self) -> impl Future<Output = Result<i128, Error>> + Send {
+    async move {
+      let mut array = [0; 16];
+      self.read_exact(&mut array).await?;
+      Ok(i128::from_be_bytes(array))
+    }
+  }
+
+  fn read_f32_be(&mut self) -> impl Future<Output = Result<f32, Error>> + Send {
+    async move {
+      let mut array = [0; 4];
+      self.read_exact(&mut array).await?;
+      Ok(f32::from_be_bytes(array))
+    }
+  }
+
+  fn read_f64_be(&mut self) -> impl Future<Output = Result<f64, Error>> + Send {
+    async move {
+      let mut array = [0; 8];
+      self.read_exact(&mut array).await?;
+      Ok(f64::from_be_bytes(array))
+    }
+  }
+
+  fn read_u16_le(&mut self) -> impl Future<Output = Result<u16, Error>> + Send {
+    async move {
+      let mut array = [0; 2];
+      self.read_exact(&mut array).await?;
+      Ok(u16::from_le_bytes(array))
+    }
+  }
+
+  fn read_u32_le(&mut self) -> impl Future<Output = Result<u32, Error>> + Send {
+    async move {
+      let mut array = [0; 4];
+      self.read_exact(&mut array).await?;
+      Ok(u32::from_le_bytes(array))
+    }
+  }
+
+  fn read_u64_le(&mut self) -> impl Future<Output = Result<u64, Error>> + Send {
+    async move {
+      let mut array = [0; 8];
+      self.read_exact(&mut array).await?;
+      Ok(u64::from_le_bytes(array))
+    }
+  }
+
+  fn read_u128_le(&mut self) -> impl Future<Output = Result<u128, Error>> + Send {
+    async move {
+      let mut array = [0; 16];
+      self.read_exact(&mut array).await?;
+      Ok(u128::from_le_bytes(array))
+    }
+  }
+
+  fn read_i16_le(&mut self) -> impl Future<Output = Result<i16, Error>> + Send {
+    async move {
+      let mut array = [0; 2];
+      self.read_exact(&mut array).await?;
+      Ok(i16::from_le_bytes(array))
+    }
+  }
+
+  fn read_i32_le(&mut self) -> impl Future<Output = Result<i32, Error>> + Send {
+    async move {
+      let mut array = [0; 4];
+      self.read_exact(&mut array).await?;
+      Ok(i32::from_le_bytes(array))
+    }
+  }
+
+  fn read_i64_le(&mut self) -> impl Future<Output = Result<i64, Error>> + Send {
+    async move {
+      let mut array = [0; 8];
+      self.read_exact(&mut array).await?;
+      Ok(i64::from_le_bytes(array))
+    }
+  }
+
+  fn read_i128_le(&mut self) -> impl Future<Output = Result<i128, Error>> + Send {
+    async move {
+      let mut array = [0; 16];
+      self.read_exact(&mut array).await?;
+      Ok(i128::from_le_bytes(array))
+    }
+  }
+
+  fn read_f32_le(&mut self) -> impl Future<Output = Result<f32, Error>> + Send {
+    async move {
+      let mut array = [0; 4];
+      self.read_exact(&mut array).await?;
+      Ok(f32::from_le_bytes(array))
+    }
+  }
+
+  fn read_f64_le(&mut self) -> impl Future<Output = Result<f64, Error>> + Send {
+    async move {
+      let mut array = [0; 8];
+      self.read_exact(&mut array).await?;
+      Ok(f64::from_le_bytes(array))
+    }
+  }
+
+  /// read_uleb128 reads unsigned LEB128 (DWARF, WebAssembly).
+  /// Overlong encodings and values over u64 are errors.
+  fn read_uleb128(&mut self) -> impl Future<Output = Result<u64, Error>> + Send {
+    read_varint(self, Uleb128::default())
+  }
+
+  /// read_sleb128 reads signed LEB128 (DWARF, WebAssembly).
+  /// Overlong encodings and values over i64 are errors.
+  fn read_sleb128(&mut self) -> impl Future<Output = Result<i64, Error>> + Send {
+    read_varint(self, Sleb128::default())
+  }
+
+  /// read_zigzag_varint reads protobuf style sint64, a zigzag encoded uleb128.
+  fn read_zigzag_varint(&mut self) -> impl Future<Output = Result<i64, Error>> + Send {
+    async move { Ok(decode_zigzag(self.read_uleb128().await?)) }
+  }
+
+  /// read_vlq reads a big-endian variable-length quantity (MIDI).
+  /// Leading 0x80 bytes and values over u64 are errors.
+  fn read_vlq(&mut self) -> impl Future<Output = Result<u64, Error>> + Send {
+    read_varint(self, Vlq::default())
+  }
+
+  /// read_ascii_string for C like ascii string.This function finishes find end marker 0x00.
+  fn read_ascii_string(
+    &mut self,
+    size: usize,
+  ) -> impl Future<Output = Result<String, Error>> + Send {
+    async move {
+      let array = self.read_bytes_as_vec(size).await?;
+      // same as BinaryReader, a byte over 0x7f is read as U+0080..U+00FF
+      let strings = array
+        .iter()
+        .take_while(|b| **b != 0)
+        .map(|b| *b as char)
+        .collect();
+      Ok(strings)
+    }
+  }
+
+  /// read_utf16_string for utf16 string. use endien
+  /// "size" refers to the number of bytes.
+  fn read_utf16_string(
+    &mut self,
+    size: usize,
+  ) -> impl Future<Output = Result<String, Error>> + Send {
+    async move {
+      let size = size / 2 * 2;
+      let array = self.read_bytes_as_vec(size).await?;
+      let chunks = array.chunks_exact(2);
+      let units: Vec<u16> = match self.endian() {
+        Endian::BigEndian => chunks.map(|c| u16::from_be_bytes([c[0], c[1]])).collect(),
+        Endian::LittleEndian => chunks.map(|c| u16::from_le_bytes([c[0], c[1]])).collect(),
+      };
+      let res = String::from_utf16(&units);
+      match res {
+        Ok(strings) => Ok(strings),
+        _ => {
+          let offset = start_offset(self, size).await?;
+          let encoding = "UTF-16";
+          Err(BinError::InvalidString { offset, encoding }.into())
+        }
+      }
+    }
+  }
+
+  fn read_utf16be_string(
+    &mut self,
+    size: usize,
+  ) -> impl Future<Output = Result<String, Error>> + Send {
+    async move {
+      let endian = self.endian();
+      self.set_endian(Endian::BigEndian);
+      let result = self.read_utf16_string(size).await;
+      self.set_endian(endian);
+      result
+    }
+  }
+
+  fn read_utf16le_string(
+    &mut self,
+    size: usize,
+  ) -> impl Future<Output = Result<String, Error>> + Send {
+    async move {
+      let endian = self.endian();
+      self.set_endian(Endian::LittleEndian);
+      let result = self.read_utf16_string(size).await;
+      self.set_endian(endian);
+      result
+    }
+  }
+
+  fn read_utf8_string(
+    &mut self,
+    size: usize,
+  ) -> impl Future<Output = Result<String, Error>> + Send {
+    async move {
+      let array = self.read_bytes_as_vec(size).await?;
+      match crate::codec::decode_utf8(array, size) {
+        Ok(string) => Ok(string),
+        Err(e) => Err(malformed(self, e).await),
+      }
+    }
+  }
+
+  /// read_fixed_string reads a string in a `width` bytes field padded with NUL or space.
+  /// `width` bytes are always consumed. UTF-16 needs an even `width`.
+  fn read_fixed_string(
+    &mut self,
+    width: usize,
+    code: CodeType,
+    trim: Trim,
+  ) -> impl Future<Output = Result<String, Error>> + Send {
+    async move {
+      check_supported(code)?;
+      check_width(width, code)?;
+      let array = self.read_bytes_as_vec(width).await?;
+      match crate::codec::decode_fixed(&array, code, trim) {
+        Ok(string) => Ok(string),
+        Err(e) => Err(malformed(self, e).await),
+      }
+    }
+  }
+
+  /// read_pascal_bytes reads bytes after a length prefix in the reader's endian.
+  fn read_pascal_bytes(
+    &mut self,
+    prefix: LengthPrefix,
+  ) -> impl Future<Output = Result<Vec<u8>, Error>> + Send {
+    async move {
+      let len = match prefix {
+        LengthPrefix::U8 => self.read_u8().await? as usize,
+        LengthPrefix::U16 => self.read_u16().await? as usize,
+        LengthPrefix::U32 => self.read_u32().await? as usize,
+      };
+      self.read_bytes_as_vec(len).await
+    }
+  }
+
+  /// read_pascal_string reads utf8 string after a length prefix in the reader's endian.
+  fn read_pascal_string(
+    &mut self,
+    prefix: LengthPrefix,
+  ) -> impl Future<Output = Result<String, Error>> + Send {
+    async move {
+      let array = self.read_pascal_bytes(prefix).await?;
+      let len = array.len();
+      match crate::codec::decode_utf8(array, len) {
+        Ok(string) => Ok(string),
+        Err(e) => Err(malformed(self, e).await),
+      }
+    }
+  }
+
+  /// read_until reads bytes until `delimiter` or the end of data.
+  /// `delimiter` is included, like `BufRead::read_until`.
+  fn read_until(&mut self, delimiter: u8) -> impl Future<Output = Result<Vec<u8>, Error>> + Send {
+    async move {
+      let mut array = Vec::new();
+      while let Some(b) = end_of_data(self.read_byte().await)? {
+        array.push(b);
+        if b == delimiter {
+          break;
+        }
+      }
+      Ok(array)
+    }
+  }
+
+  /// read_cstring reads utf8 string until NUL. NUL is consumed but not returned.
+  /// It is an error if data ends before NUL.
+  fn read_cstring(&mut self) -> impl Future<Output = Result<String, Error>> + Send {
+    async move {
+      let array = self.read_until(0).await?;
+      match crate::codec::decode_cstring(array) {
+        Ok(string) => Ok(string),
+        Err(e) => Err(malformed(self, e).await),
+      }
+    }
+  }
+
+  /// read_line_bytes reads a line without "\n" or "\r\n".
+  /// It returns None at the end of data.
+  fn read_line_bytes(&mut self) -> impl Future<Output = Result<Option<Vec<u8>>, Error>> + Send {
+    async move { Ok(crate::codec::line_bytes(self.read_until(b'\n').await?)) }
+  }
+
+  /// read_line reads a utf8 line without "\n" or "\r\n".
+  /// It returns None at the end of data.
+  fn read_line(&mut self) -> impl Future<Output = Result<Option<String>, Error>> + Send {
+    async move {
+      let array = self.read_until(b'\n').await?;
+      match crate::codec::decode_line(array) {
+        Ok(line) => Ok(line),
+        Err(e) => Err(malformed(self, e).await),
+      }
+    }
+  }
+
+  /// read_local_string reads `size` bytes string in `code`, feature="codec".
+  /// It ends at NUL, and malformed bytes are errors. See `read_local_string_with`.
+  #[cfg(feature = "codec")]
+  fn read_local_string(
+    &mut self,
+    size: usize,
+    code: CodeType,
+  ) -> impl Future<Output = Result<String, Error>> + Send {
+    self.read_local_string_with(size, code, DecodeOptions::default())
+  }
+
+  /// read_local_string_with reads `size` bytes string in `code` with `options`, feature="codec".
+  /// `size` bytes are always consumed. A leading BOM of UTF-8 or UTF-16 is removed.
+  #[cfg(feature = "codec")]
+  fn read_local_string_with(
+    &mut self,
+    size: usize,
+    code: CodeType,
+    options: DecodeOptions,
+  ) -> impl Future<Output = Result<String, Error>> + Send {
+    async move {
+      let array = self.read_bytes_as_vec(size).await?;
+      match crate::codec::decode_local(&array, code, options) {
+        Ok(string) => Ok(string),
+        Err(e) => Err(malformed(self, e).await),
+      }
+    }
+  }
+
+  /// skip size byte
+  fn skip_ptr(&mut self, size: usize) -> impl Future<Output = Result<usize, Error>> + Send;
+
+  fn offset(&mut self) -> impl Future<Output = Result<u64, Error>> + Send;
+  fn seek(&mut self, seek: SeekFrom) -> impl Future<Output = Result<u64, Error>> + Send;
+}
+
+// start_offset returns the offset `len` bytes before the current offset,
+// where a failed value starts.
+async fn start_offset<R: AsyncBinaryReader + ?Sized>(
+  reader: &mut R,
+  len: usize,
+) -> Result<u64, Error> {
+  Ok(reader.offset().await?.saturating_sub(len as u64))
+}
+
+// malformed returns the error of `e` at the offset where the failed value starts.
+async fn malformed<R: AsyncBinaryReader + ?Sized>(reader: &mut R, e: Malformed) -> Error {
+  match start_offset(reader, e.len()).await {
+    Ok(offset) => e.error(offset),
+    Err(err) => err,
+  }
+}
+
+// read_varint reads bytes until `decoder` returns the value.
+async fn read_varint<R: AsyncBinaryReader + ?Sized, V: Varint + Send>(
+  reader: &mut R,
+  mut decoder: V,
+) -> Result<V::Value, Error> {
+  loop {
+    let b = reader.read_byte().await?;
+    match decoder.push(b) {
+      Ok(Some(value)) => return Ok(value),
+      Ok(None) => {}
+      Err(e) => return Err(malformed(reader, e).await),
+    }
+  }
+}
@@ -0,0 +1,29 @@
+use crate::Endian;
+use std::io::{Error, SeekFrom};
+use tokio::io::AsyncBufRead;
+
+use super::source::{impl_async_reader, BytesCore, Tokio};
+use super::AsyncBinaryReader;
+
+/// using AsyncBytesReader feature async only
+///
+/// AsyncBytesReader is async functions bytesreader on stream
+///
+/// The source does not have to seek, so a socket can be read.
+/// `offset` counts the bytes read, and `seek` can move forward only.
+#[derive(Copy, Debug, Clone)]
+pub struct AsyncBytesReader<R> {
+  core: BytesCore<Tokio<R>>,
+  endian: Endian,
+}
+
+impl<R: AsyncBufRead + Send + Unpin> AsyncBytesReader<R> {
+  pub fn new(reader: R) -> AsyncBytesReader<R> {
+    AsyncBytesReader {
+      core: BytesCore::new(Tokio(reader), "AsyncBytesReader"),
+      endian: crate::system_endian(),
+    }
+  }
+}
+
+impl_async_reader!(AsyncBytesReader, AsyncBufRead);
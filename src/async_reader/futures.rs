@@ -0,0 +1,58 @@
+use crate::Endian;
+use futures_io::{AsyncBufRead, AsyncSeek};
+use std::io::{Error, SeekFrom};
+
+use super::source::{impl_async_reader, BytesCore, Futures, StreamCore};
+use super::AsyncBinaryReader;
+
+/// FuturesBytesReader is `AsyncBytesReader` on `futures::io::AsyncBufRead`, feature="futures".
+///
+/// It runs on any executor, for example async-std or smol.
+/// `offset` counts the bytes read, and `seek` can move forward only.
+/// ```
+/// use bin_rs::async_reader::*;
+/// use std::io::Error;
+///
+/// async fn test() -> Result<(), Error> {
+///   let buffer: &[u8] = b"\x01\x02bin\x00";
+///   let mut reader = FuturesBytesReader::new(buffer);
+///   assert_eq!(reader.read_u16_be().await?, 0x0102);
+///   assert_eq!(reader.read_cstring().await?, "bin");
+///   Ok(())
+/// }
+/// ```
+#[derive(Copy, Debug, Clone)]
+pub struct FuturesBytesReader<R> {
+  core: BytesCore<Futures<R>>,
+  endian: Endian,
+}
+
+impl<R: AsyncBufRead + Send + Unpin> FuturesBytesReader<R> {
+  pub fn new(reader: R) -> FuturesBytesReader<R> {
+    FuturesBytesReader {
+      core: BytesCore::new(Futures(reader), "FuturesBytesReader"),
+      endian: crate::system_endian(),
+    }
+  }
+}
+
+impl_async_reader!(FuturesBytesReader, AsyncBufRead);
+
+/// FuturesStreamReader is `AsyncStreamReader` on `futures::io::AsyncBufRead` and `AsyncSeek`,
+/// feature="futures".
+#[derive(Copy, Debug, Clone)]
+pub struct FuturesStreamReader<R> {
+  core: StreamCore<Futures<R>>,
+  endian: Endian,
+}
+
+impl<R: AsyncBufRead + AsyncSeek + Send + Unpin> FuturesStreamReader<R> {
+  pub fn new(reader: R) -> FuturesStreamReader<R> {
+    FuturesStreamReader {
+      core: StreamCore::new(Futures(reader)),
+      endian: crate::system_endian(),
+    }
+  }
+}
+
+impl_async_reader!(FuturesStreamReader, AsyncBufRead, AsyncSeek);
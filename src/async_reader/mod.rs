@@ -0,0 +1,29 @@
+//! async_reader is a test feature.
+//! This async is dipoable.
+//! now refactoring
+//! 0.0.11 AsyncBinaryReader trait, AsyncStreamReader seeks on AsyncSeek
+//! 0.0.11 FuturesBytesReader, FuturesStreamReader on futures-io (feature="futures")
+//!
+//! AsyncBytesReader and AsyncStreamReader need feature="async" (tokio).
+//! The Futures readers share `AsyncBinaryReader`, so a parser runs on both.
+//!
+//! Methods return `std::io::Error` like `BinaryReader`.
+//! `FromBinary`, `with_context` and `sub_reader` are sync only.
+//! Read a record with `read_bytes_as_vec`, and parse it with `BytesReader`.
+
+mod binary;
+#[cfg(feature = "async")]
+mod bytes;
+#[cfg(feature = "futures")]
+mod futures;
+#[cfg(any(feature = "async", feature = "futures"))]
+mod source;
+#[cfg(feature = "async")]
+mod stream;
+pub use self::binary::AsyncBinaryReader;
+#[cfg(feature = "async")]
+pub use self::bytes::AsyncBytesReader;
+#[cfg(feature = "futures")]
+pub use self::futures::{FuturesBytesReader, FuturesStreamReader};
+#[cfg(feature = "async")]
+pub use self::stream::AsyncStreamReader;
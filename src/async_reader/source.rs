@@ -0,0 +1,261 @@
+use crate::error::BinError;
+use std::future::Future;
+use std::io::{Error, SeekFrom};
+
+// Source is a buffered async stream. Tokio and futures-io streams are wrapped in it,
+// so every async reader shares one implementation.
+pub(crate) trait Source: Send {
+  fn fill_buf(&mut self) -> impl Future<Output = Result<&[u8], Error>> + Send;
+  fn consume(&mut self, len: usize);
+}
+
+// SeekSource is a Source which can seek.
+pub(crate) trait SeekSource: Source {
+  fn seek(&mut self, seek: SeekFrom) -> impl Future<Output = Result<u64, Error>> + Send;
+}
+
+// Tokio wraps a stream of tokio::io.
+#[cfg(feature = "async")]
+#[derive(Copy, Debug, Clone)]
+pub(crate) struct Tokio<R>(pub(crate) R);
+
+#[cfg(feature = "async")]
+impl<R: tokio::io::AsyncBufRead + Send + Unpin> Source for Tokio<R> {
+  fn fill_buf(&mut self) -> impl Future<Output = Result<&[u8], Error>> + Send {
+    tokio::io::AsyncBufReadExt::fill_buf(&mut self.0)
+  }
+
+  fn consume(&mut self, len: usize) {
+    tokio::io::AsyncBufReadExt::consume(&mut self.0, len)
+  }
+}
+
+#[cfg(feature = "async")]
+impl<R: tokio::io::AsyncBufRead + tokio::io::AsyncSeek + Send + Unpin> SeekSource for Tokio<R> {
+  fn seek(&mut self, seek: SeekFrom) -> impl Future<Output = Result<u64, Error>> + Send {
+    tokio::io::AsyncSeekExt::seek(&mut self.0, seek)
+  }
+}
+
+// Futures wraps a stream of futures-io.
+#[cfg(feature = "futures")]
+#[derive(Copy, Debug, Clone)]
+pub(crate) struct Futures<R>(pub(crate) R);
+
+#[cfg(feature = "futures")]
+impl<R: futures_io::AsyncBufRead + Send + Unpin> Source for Futures<R> {
+  fn fill_buf(&mut self) -> impl Future<Output = Result<&[u8], Error>> + Send {
+    futures_util::io::AsyncBufReadExt::fill_buf(&mut self.0)
+  }
+
+  fn consume(&mut self, len: usize) {
+    futures_util::io::AsyncBufReadExt::consume_unpin(&mut self.0, len)
+  }
+}
+
+#[cfg(feature = "futures")]
+impl<R: futures_io::AsyncBufRead + futures_io::AsyncSeek + Send + Unpin> SeekSource for Futures<R> {
+  fn seek(&mut self, seek: SeekFrom) -> impl Future<Output = Result<u64, Error>> + Send {
+    futures_util::io::AsyncSeekExt::seek(&mut self.0, seek)
+  }
+}
+
+// fill reads `array` until the end of data, and returns the number of bytes read.
+async fn fill<S: Source>(source: &mut S, array: &mut [u8]) -> Result<usize, Error> {
+  let mut filled = 0;
+  while filled < array.len() {
+    let buffer = source.fill_buf().await?;
+    if buffer.is_empty() {
+      break;
+    }
+    let len = buffer.len().min(array.len() - filled);
+    array[filled..filled + len].copy_from_slice(&buffer[..len]);
+    source.consume(len);
+    filled += len;
+  }
+  Ok(filled)
+}
+
+fn eof(offset: u64, requested: usize, available: usize) -> Error {
+  BinError::UnexpectedEof {
+    offset,
+    requested,
+    available,
+  }
+  .into()
+}
+
+// BytesCore reads a source which can not seek, and counts the bytes read.
+// `seek` can move forward only.
+#[derive(Copy, Debug, Clone)]
+pub(crate) struct BytesCore<S> {
+  source: S,
+  ptr: u64,
+  name: &'static str,
+}
+
+impl<S: Source> BytesCore<S> {
+  pub(crate) fn new(source: S, name: &'static str) -> Self {
+    Self {
+      source,
+      ptr: 0,
+      name,
+    }
+  }
+
+  pub(crate) async fn read_exact(&mut self, array: &mut [u8]) -> Result<(), Error> {
+    let filled = fill(&mut self.source, array).await?;
+    self.ptr += filled as u64;
+    if filled < array.len() {
+      return Err(eof(self.ptr - filled as u64, array.len(), filled));
+    }
+    Ok(())
+  }
+
+  // read_bytes_no_move depends on the read buffer size.
+  pub(crate) async fn read_bytes_no_move(&mut self, len: usize) -> Result<Vec<u8>, Error> {
+    let buffer = self.source.fill_buf().await?;
+    if buffer.len() < len {
+      return Err(eof(self.ptr, len, buffer.len()));
+    }
+    Ok(buffer[..len].to_vec())
+  }
+
+  // skip_ptr consumes `size` bytes in chunks of the read buffer.
+  pub(crate) async fn skip_ptr(&mut self, size: usize) -> Result<usize, Error> {
+    let mut skipped = 0;
+    while skipped < size {
+      let available = self.source.fill_buf().await?.len();
+      if available == 0 {
+        return Err(eof(self.ptr - skipped as u64, size, skipped));
+      }
+      let len = available.min(size - skipped);
+      self.source.consume(len);
+      self.ptr += len as u64;
+      skipped += len;
+    }
+    Ok(size)
+  }
+
+  pub(crate) async fn offset(&mut self) -> Result<u64, Error> {
+    Ok(self.ptr)
+  }
+
+  pub(crate) async fn seek(&mut self, seek: SeekFrom) -> Result<u64, Error> {
+    let pos = match seek {
+      SeekFrom::Start(pos) => pos as i128,
+      SeekFrom::Current(pos) => self.ptr as i128 + pos as i128,
+      SeekFrom::End(_) => {
+        let s = format!("{} can not seek from the end", self.name);
+        return Err(BinError::InvalidInput(s).into());
+      }
+    };
+    if pos < self.ptr as i128 {
+      let s = format!(
+        "{} can not seek back to {} from {}",
+        self.name, pos, self.ptr
+      );
+      return Err(BinError::InvalidInput(s).into());
+    }
+    self.skip_ptr((pos - self.ptr as i128) as usize).await?;
+    Ok(self.ptr)
+  }
+}
+
+// StreamCore reads a source which can seek. A short read goes back to its start.
+#[derive(Copy, Debug, Clone)]
+pub(crate) struct StreamCore<S> {
+  source: S,
+}
+
+impl<S: SeekSource> StreamCore<S> {
+  pub(crate) fn new(source: S) -> Self {
+    Self { source }
+  }
+
+  pub(crate) async fn read_exact(&mut self, array: &mut [u8]) -> Result<(), Error> {
+    let filled = fill(&mut self.source, array).await?;
+    if filled < array.len() {
+      let offset = self.offset().await? - filled as u64;
+      self.source.seek(SeekFrom::Start(offset)).await?;
+      return Err(eof(offset, array.len(), filled));
+    }
+    Ok(())
+  }
+
+  // read_bytes_no_move depends on the read buffer size.
+  pub(crate) async fn read_bytes_no_move(&mut self, len: usize) -> Result<Vec<u8>, Error> {
+    let buffer = self.source.fill_buf().await?;
+    if buffer.len() >= len {
+      return Ok(buffer[..len].to_vec());
+    }
+    let available = buffer.len();
+    let offset = self.offset().await?;
+    Err(eof(offset, len, available))
+  }
+
+  // skip_ptr consumes buffered bytes, or seeks over `size` bytes.
+  // Skipping past the end is an error and the offset is not moved.
+  pub(crate) async fn skip_ptr(&mut self, size: usize) -> Result<usize, Error> {
+    if size <= self.source.fill_buf().await?.len() {
+      self.source.consume(size);
+      return Ok(size);
+    }
+    let offset = self.offset().await?;
+    let end = self.source.seek(SeekFrom::End(0)).await?;
+    let available = end.saturating_sub(offset);
+    if size as u64 > available {
+      self.source.seek(SeekFrom::Start(offset)).await?;
+      return Err(eof(offset, size, available as usize));
+    }
+    self
+      .source
+      .seek(SeekFrom::Start(offset + size as u64))
+      .await?;
+    Ok(size)
+  }
+
+  pub(crate) async fn offset(&mut self) -> Result<u64, Error> {
+    self.source.seek(SeekFrom::Current(0)).await
+  }
+
+  pub(crate) async fn seek(&mut self, seek: SeekFrom) -> Result<u64, Error> {
+    self.source.seek(seek).await
+  }
+}
+
+// impl_async_reader implements AsyncBinaryReader for `$reader` by its `core` field.
+macro_rules! impl_async_reader {
+  ($reader:ident, $($bound:path),+) => {
+    impl<R: $($bound +)+ Send + Unpin> AsyncBinaryReader for $reader<R> {
+      fn set_endian(&mut self, endian: Endian) {
+        self.endian = endian;
+      }
+
+      fn endian(&self) -> Endian {
+        self.endian
+      }
+
+      async fn read_exact(&mut self, array: &mut [u8]) -> Result<(), Error> {
+        self.core.read_exact(array).await
+      }
+
+      async fn read_bytes_no_move(&mut self, len: usize) -> Result<Vec<u8>, Error> {
+        self.core.read_bytes_no_move(len).await
+      }
+
+      async fn skip_ptr(&mut self, size: usize) -> Result<usize, Error> {
+        self.core.skip_ptr(size).await
+      }
+
+      async fn offset(&mut self) -> Result<u64, Error> {
+        self.core.offset().await
+      }
+
+      async fn seek(&mut self, seek: SeekFrom) -> Result<u64, Error> {
+        self.core.seek(seek).await
+      }
+    }
+  };
+}
+pub(crate) use impl_async_reader;
@@ -0,0 +1,40 @@
+use crate::Endian;
+use std::io::{Error, SeekFrom};
+use tokio::io::{AsyncBufRead, AsyncSeek};
+
+use super::source::{impl_async_reader, StreamCore, Tokio};
+use super::AsyncBinaryReader;
+
+/// AsyncStreamReader is an async reader on a seekable stream, feature="async".
+///
+/// Use it for files, `AsyncBytesReader` for sockets.
+/// ```
+/// use bin_rs::async_reader::*;
+/// use std::io::{Cursor, Error, SeekFrom};
+///
+/// async fn test() -> Result<(), Error> {
+///   let mut reader = AsyncStreamReader::new(Cursor::new(b"\x00\x00\x00\x08data\x01\x02".to_vec()));
+///   let offset = reader.read_u32_be().await?;
+///   reader.seek(SeekFrom::Start(offset as u64)).await?;
+///   assert_eq!(reader.read_u16_le().await?, 0x0201);
+///   reader.seek(SeekFrom::Start(4)).await?;
+///   assert_eq!(reader.read_ascii_string(4).await?, "data");
+///   Ok(())
+/// }
+/// ```
+#[derive(Copy, Debug, Clone)]
+pub struct AsyncStreamReader<R> {
+  core: StreamCore<Tokio<R>>,
+  endian: Endian,
+}
+
+impl<R: AsyncBufRead + AsyncSeek + Send + Unpin> AsyncStreamReader<R> {
+  pub fn new(reader: R) -> AsyncStreamReader<R> {
+    AsyncStreamReader {
+      core: StreamCore::new(Tokio(reader)),
+      endian: crate::system_endian(),
+    }
+  }
+}
+
+impl_async_reader!(AsyncStreamReader, AsyncBufRead, AsyncSeek);
@@ -0,0 +1,484 @@
+use crate::error::BinError;
+use crate::reader::CodeType;
+use crate::writer::{
+  ascii_bytes, fixed_bytes, unmappable, utf16_bytes, EncodeOptions, Padding, Placeholder,
+  Placeholders,
+};
+use crate::Endian;
+use std::future::Future;
+use std::io::{Error, SeekFrom};
+
+/// 0.0.11 AsyncBinaryWriter is the async version of `BinaryWriter`.
+///
+/// An implementation only has to provide `write_bytes`, endian handling,
+/// `offset`, `seek` and `placeholders`, like `BinaryWriter`.
+/// Every future is `Send`, so a generic writer can run in a spawned task.
+/// ```
+/// use bin_rs::async_writer::*;
+/// use bin_rs::Endian;
+/// use std::io::Error;
+///
+/// async fn write_frame<W: AsyncBinaryWriter>(writer: &mut W, body: &[u8]) -> Result<(), Error> {
+///   writer.write_u8(0x01).await?;
+///   writer.write_u32_be(body.len() as u32).await?;
+///   writer.write_bytes(body).await?;
+///   writer.flush().await
+/// }
+/// ```
+pub trait AsyncBinaryWriter: Send {
+  fn set_endian(&mut self, endian: Endian);
+  fn endian(&self) -> Endian;
+
+  /// write_bytes writes whole `array` at the current offset.
+  fn write_bytes(&mut self, array: &[u8]) -> impl Future<Output = Result<(), Error>> + Send;
+
+  fn write_byte(&mut self, num: u8) -> impl Future<Output = Result<(), Error>> + Send {
+    async move { self.write_bytes(&[num]).await }
+  }
+
+  fn write_u8(&mut self, num: u8) -> impl Future<Output = Result<(), Error>> + Send {
+    self.write_byte(num)
+  }
+
+  fn write_i8(&mut self, num: i8) -> impl Future<Output = Result<(), Error>> + Send {
+    self.write_byte(num as u8)
+  }
+
+  fn write_u16(&mut self, num: u16) -> impl Future<Output = Result<(), Error>> + Send {
+    async move {
+      match self.endian() {
+        Endian::BigEndian => self.write_u16_be(num).await,
+        Endian::LittleEndian => self.write_u16_le(num).await,
+      }
+    }
+  }
+
+  fn write_u32(&mut self, num: u32) -> impl Future<Output = Result<(), Error>> + Send {
+    async move {
+      match self.endian() {
+        Endian::BigEndian => self.write_u32_be(num).await,
+        Endian::LittleEndian => self.write_u32_le(num).await,
+      }
+    }
+  }
+
+  fn write_u64(&mut self, num: u64) -> impl Future<Output = Result<(), Error>> + Send {
+    async move {
+      match self.endian() {
+        Endian::BigEndian => self.write_u64_be(num).await,
+        Endian::LittleEndian => self.write_u64_le(num).await,
+      }
+    }
+  }
+
+  fn write_u128(&mut self, num: u128) -> impl Future<Output = Result<(), Error>> + Send {
+    async move {
+      match self.endian() {
+        Endian::BigEndian => self.write_u128_be(num).await,
+        Endian::LittleEndian => self.write_u128_le(num).await,
+      }
+    }
+  }
+
+  fn write_i16(&mut self, num: i16) -> impl Future<Output = Result<(), Error>> + Send {
+    async move {
+      match self.endian() {
+        Endian::BigEndian => self.write_i16_be(num).await,
+        Endian::LittleEndian => self.write_i16_le(num).await,
+      }
+    }
+  }
+
+  fn write_i32(&mut self, num: i32) -> impl Future<Output = Result<(), Error>> + Send {
+    async move {
+      match self.endian() {
+        Endian::BigEndian => self.write_i32_be(num).await,
+        Endian::LittleEndian => self.write_i32_le(num).await,
+      }
+    }
+  }
+
+  fn write_i64(&mut self, num: i64) -> impl Future<Output = Result<(), Error>> + Send {
+    async move {
+      match self.endian() {
+        Endian::BigEndian => self.write_i64_be(num).await,
+        Endian::LittleEndian => self.write_i64_le(num).await,
+      }
+    }
+  }
+
+  fn write_i128(&mut self, num: i128) -> impl Future<Output = Result<(), Error>> + Send {
+    async move {
+      match self.endian() {
+        Endian::BigEndian => self.write_i128_be(num).await,
+        Endian::LittleEndian => self.write_i128_le(num).await,
+      }
+    }
+  }
+
+  fn write_f32(&mut self, num: f32) -> impl Future<Output = Result<(), Error>> + Send {
+    async move {
+      match self.endian() {
+        Endian::BigEndian => self.write_f32_be(num).await,
+        Endian::LittleEndian => self.write_f32_le(num).await,
+      }
+    }
+  }
+
+  fn write_f64(&mut self, num: f64) -> impl Future<Output = Result<(), Error>> + Send {
+    async move {
+      match self.endian() {
+        Endian::BigEndian => self.write_f64_be(num).await,
+        Endian::LittleEndian => self.write_f64_le(num).await,
+      }
+    }
+  }
+
+  fn write_u16_be(&mut self, num: u16) -> impl Future<Output = Result<(), Error>> + Send {
+    async move { self.write_bytes(&num.to_be_bytes()).await }
+  }
+
+  fn write_u32_be(&mut self, num: u32) -> impl Future<Output = Result<(), Error>> + Send {
+    async move { self.write_bytes(&num.to_be_bytes()).await }
+  }
+
+  fn write_u64_be(&mut self, num: u64) -> impl Future<Output = Result<(), Error>> + Send {
+    async move { self.write_bytes(&num.to_be_bytes()).await }
+  }
+
+  fn write_u128_be(&mut self, num: u128) -> impl Future<Output = Result<(), Error>> + Send {
+    async move { self.write_bytes(&num.to_be_bytes()).await }
+  }
+
+  fn write_i16_be(&mut self, num: i16) -> impl Future<Output = Result<(), Error>> + Send {
+    async move { self.write_bytes(&num.to_be_bytes()).await }
+  }
+
+  fn write_i32_be(&mut self, num: i32) -> impl Future<Output = Result<(), Error>> + Send {
+    async move { self.write_bytes(&num.to_be_bytes()).await }
+  }
+
+  fn write_i64_be(&mut self, num: i64) -> impl Future<Output = Result<(), Error>> + Send {
+    async move { self.write_bytes(&num.to_be_bytes()).await }
+  }
+
+  fn write_i128_be(&mut self, num: i128) -> impl Future<Output = Result<(), Error>> + Send {
+    async move { self.write_bytes(&num.to_be_bytes()).await }
+  }
+
+  fn write_f32_be(&mut self, num: f32) -> impl Future<Output = Result<(), Error>> + Send {
+    async move { self.write_bytes(&num.to_be_bytes()).await }
+  }
+
+  fn write_f64_be(&mut self, num: f64) -> impl Future<Output = Result<(), Error>> + Send {
+    async move { self.write_bytes(&num.to_be_bytes()).await }
+  }
+
+  fn write_u16_le(&mut self, num: u16) -> impl Future<Output = Result<(), Error>> + Send {
+    async move { self.write_bytes(&num.to_le_bytes()).await }
+  }
+
+  fn write_u32_le(&mut self, num: u32) -> impl Future<Output = Result<(), Error>> + Send {
+    async move { self.write_bytes(&num.to_le_bytes()).await }
+  }
+
+  fn write_u64_le(&mut self, num: u64) -> impl Future<Output = Result<(), Error>> + Send {
+    async move { self.write_bytes(&num.to_le_bytes()).await }
+  }
+
+  fn write_u128_le(&mut self, num: u128) -> impl Future<Output = Result<(), Error>> + Send {
+    async move { self.write_bytes(&num.to_le_bytes()).await }
+  }
+
+  fn write_i16_le(&mut self, num: i16) -> impl Future<Output = Result<(), Error>> + Send {
+    async move { self.write_bytes(&num.to_le_bytes()).await }
+  }
+
+  fn write_i32_le(&mut self, num: i32) -> impl Future<Output = Result<(), Error>> + Send {
+    async move { self.write_bytes(&num.to_le_bytes()).await }
+  }
+
+  fn write_i64_le(&mut self, num: i64) -> impl Future<Output = Result<(), Error>> + Send {
+    async move { self.write_bytes(&num.to_le_bytes()).await }
+  }
+
+  fn write_i128_le(&mut self, num: i128) -> impl Future<Output = Result<(), Error>> + Send {
+    async move { self.write_bytes(&num.to_le_bytes()).await }
+  }
+
+  fn write_f32_le(&mut self, num: f32) -> impl Future<Output = Result<(), Error>> + Send {
+    async move { self.write_bytes(&num.to_le_bytes()).await }
+  }
+
+  fn write_f64_le(&mut self, num: f64) -> impl Future<Output = Result<(), Error>> + Send {
+    async move { self.write_bytes(&num.to_le_bytes()).await }
+  }
+
+  /// write_uleb128 writes unsigned LEB128 in the shortest form.
+  fn write_uleb128(&mut self, num: u64) -> impl Future<Output = Result<(), Error>> + Send {
+    async move {
+      let mut array = Vec::new();
+      crate::varint::encode_uleb128(num, &mut array);
+      self.write_bytes(&array).await
+    }
+  }
+
+  /// write_sleb128 writes signed LEB128 in the shortest form.
+  fn write_sleb128(&mut self, num: i64) -> impl Future<Output = Result<(), Error>> + Send {
+    async move {
+      let mut array = Vec::new();
+      crate::varint::encode_sleb128(num, &mut array);
+      self.write_bytes(&array).await
+    }
+  }
+
+  /// write_zigzag_varint writes protobuf style sint64.
+  fn write_zigzag_varint(&mut self, num: i64) -> impl Future<Output = Result<(), Error>> + Send {
+    async move {
+      let mut array = Vec::new();
+      crate::varint::encode_zigzag_varint(num, &mut array);
+      self.write_bytes(&array).await
+    }
+  }
+
+  /// write_vlq writes a big-endian variable-length quantity (MIDI).
+  fn write_vlq(&mut self, num: u64) -> impl Future<Output = Result<(), Error>> + Send {
+    async move {
+      let mut array = Vec::new();
+      crate::varint::encode_vlq(num, &mut array);
+      self.write_bytes(&array).await
+    }
+  }
+
+  /// write_ascii_string writes one byte per charactor, the counterpart of `read_ascii_string`.
+  /// The end marker 0x00 is not written. Returns the number of bytes.
+  fn write_ascii_string(
+    &mut self,
+    string: &str,
+  ) -> impl Future<Output = Result<usize, Error>> + Send {
+    async move {
+      let array = match ascii_bytes(string) {
+        Ok(array) => array,
+        Err((character, position)) => {
+          let offset = self.offset().await? + position as u64;
+          return Err(unmappable(offset, "US-ASCII", character));
+        }
+      };
+      self.write_bytes(&array).await?;
+      Ok(array.len())
+    }
+  }
+
+  /// write_utf16_string writes utf16 string. use endien
+  /// Returns the number of bytes.
+  fn write_utf16_string(
+    &mut self,
+    string: &str,
+  ) -> impl Future<Output = Result<usize, Error>> + Send {
+    let endian = self.endian();
+    self.write_utf16_string_with(string, endian, EncodeOptions::default())
+  }
+
+  fn write_utf16be_string(
+    &mut self,
+    string: &str,
+  ) -> impl Future<Output = Result<usize, Error>> + Send {
+    self.write_utf16_string_with(string, Endian::BigEndian, EncodeOptions::default())
+  }
+
+  fn write_utf16le_string(
+    &mut self,
+    string: &str,
+  ) -> impl Future<Output = Result<usize, Error>> + Send {
+    self.write_utf16_string_with(string, Endian::LittleEndian, EncodeOptions::default())
+  }
+
+  /// write_utf16_string_with writes utf16 string in `endian` with an optional BOM and NUL.
+  /// Returns the number of bytes.
+  fn write_utf16_string_with(
+    &mut self,
+    string: &str,
+    endian: Endian,
+    options: EncodeOptions,
+  ) -> impl Future<Output = Result<usize, Error>> + Send {
+    async move {
+      let array = utf16_bytes(string, endian, options);
+      self.write_bytes(&array).await?;
+      Ok(array.len())
+    }
+  }
+
+  fn write_utf8_string(
+    &mut self,
+    string: &str,
+  ) -> impl Future<Output = Result<usize, Error>> + Send {
+    async move {
+      self.write_bytes(string.as_bytes()).await?;
+      Ok(string.len())
+    }
+  }
+
+  /// write_fixed_string writes `string` in `code` into a `width` bytes field filled with `padding`.
+  /// It is an error if the encoded string is longer than `width`. UTF-16 needs an even `width`.
+  fn write_fixed_string(
+    &mut self,
+    string: &str,
+    width: usize,
+    code: CodeType,
+    padding: Padding,
+  ) -> impl Future<Output = Result<(), Error>> + Send {
+    async move {
+      crate::reader::check_supported(code)?;
+      let offset = self.offset().await?;
+      let array = fixed_bytes(string, width, code, padding, offset)?;
+      self.write_bytes(&array).await
+    }
+  }
+
+  /// write_local_string writes `string` in `code`, feature="codec".
+  /// Characters which `code` can not represent are errors. Returns the number of bytes.
+  #[cfg(feature = "codec")]
+  fn write_local_string(
+    &mut self,
+    string: &str,
+    code: CodeType,
+  ) -> impl Future<Output = Result<usize, Error>> + Send {
+    self.write_local_string_with(string, code, EncodeOptions::default())
+  }
+
+  /// write_local_string_with writes `string` in `code` with `options`, feature="codec".
+  /// Returns the number of bytes.
+  #[cfg(feature = "codec")]
+  fn write_local_string_with(
+    &mut self,
+    string: &str,
+    code: CodeType,
+    options: EncodeOptions,
+  ) -> impl Future<Output = Result<usize, Error>> + Send {
+    async move {
+      let array = match crate::writer::local_bytes(string, code, options) {
+        Ok(array) => array,
+        Err((character, position)) => {
+          let offset = self.offset().await? + position as u64;
+          return Err(unmappable(offset, code.name(), character));
+        }
+      };
+      self.write_bytes(&array).await?;
+      Ok(array.len())
+    }
+  }
+
+  fn offset(&mut self) -> impl Future<Output = Result<u64, Error>> + Send;
+  fn seek(&mut self, seek: SeekFrom) -> impl Future<Output = Result<u64, Error>> + Send;
+
+  /// flush writes out buffered data. default does nothing.
+  fn flush(&mut self) -> impl Future<Output = Result<(), Error>> + Send {
+    async move { Ok(()) }
+  }
+
+  /// placeholders returns the unfilled placeholders of this writer.
+  fn placeholders(&mut self) -> &mut Placeholders;
+
+  /// can_seek returns false if the writer can not go back, so placeholders can not be filled.
+  /// default is true.
+  fn can_seek(&self) -> bool {
+    true
+  }
+
+  /// reserve_u16 writes a zero u16 field and returns a handle to fill it later.
+  /// It is an error on a writer which can not seek, see `can_seek`.
+  fn reserve_u16(
+    &mut self,
+    endian: Endian,
+  ) -> impl Future<Output = Result<Placeholder, Error>> + Send {
+    reserve(self, 2, endian)
+  }
+
+  fn reserve_u32(
+    &mut self,
+    endian: Endian,
+  ) -> impl Future<Output = Result<Placeholder, Error>> + Send {
+    reserve(self, 4, endian)
+  }
+
+  fn reserve_u64(
+    &mut self,
+    endian: Endian,
+  ) -> impl Future<Output = Result<Placeholder, Error>> + Send {
+    reserve(self, 8, endian)
+  }
+
+  /// fill writes `value` into the placeholder, and returns to the current offset.
+  /// The placeholder is pending until the write succeeds, so a failed fill can be retried.
+  fn fill(
+    &mut self,
+    placeholder: &Placeholder,
+    value: u64,
+  ) -> impl Future<Output = Result<(), Error>> + Send {
+    async move {
+      let bytes = placeholder.to_bytes(value)?;
+      self.placeholders().reserved(placeholder)?;
+      let current = self.offset().await?;
+      self.seek(SeekFrom::Start(placeholder.offset())).await?;
+      let written = self.write_bytes(&bytes).await;
+      if written.is_ok() {
+        self.placeholders().remove(placeholder)?;
+      }
+      self.seek(SeekFrom::Start(current)).await?;
+      written
+    }
+  }
+
+  /// fill_length fills the number of bytes written after the placeholder.
+  fn fill_length(
+    &mut self,
+    placeholder: &Placeholder,
+  ) -> impl Future<Output = Result<(), Error>> + Send {
+    async move {
+      let current = self.offset().await?;
+      let end = placeholder.end();
+      if current < end {
+        let s = format!(
+          "current offset {} is before the end of placeholder {}",
+          current, end
+        );
+        return Err(BinError::InvalidInput(s).into());
+      }
+      self.fill(placeholder, current - end).await
+    }
+  }
+
+  /// fill_offset fills the current absolute offset.
+  fn fill_offset(
+    &mut self,
+    placeholder: &Placeholder,
+  ) -> impl Future<Output = Result<(), Error>> + Send {
+    async move {
+      let current = self.offset().await?;
+      self.fill(placeholder, current).await
+    }
+  }
+
+  /// finish checks that all placeholders are filled, and flushes.
+  fn finish(&mut self) -> impl Future<Output = Result<(), Error>> + Send {
+    async move {
+      self.placeholders().check()?;
+      self.flush().await
+    }
+  }
+}
+
+// reserve writes `size` zero bytes and returns a placeholder for them.
+async fn reserve<W: AsyncBinaryWriter + ?Sized>(
+  writer: &mut W,
+  size: usize,
+  endian: Endian,
+) -> Result<Placeholder, Error> {
+  if !writer.can_seek() {
+    let s = "placeholders need a writer which can seek".to_string();
+    return Err(BinError::InvalidInput(s).into());
+  }
+  let offset = writer.offset().await?;
+  writer.write_bytes(&[0; 8][..size]).await?;
+  Ok(writer.placeholders().reserve(offset, size, endian))
+}
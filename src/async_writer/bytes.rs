@@ -0,0 +1,51 @@
+use crate::writer::Placeholders;
+use crate::Endian;
+use std::io::{Error, SeekFrom};
+use tokio::io::AsyncWrite;
+
+use super::sink::{impl_async_writer, BytesCore, Tokio};
+use super::AsyncBinaryWriter;
+
+/// AsyncBytesWriter is an async writer on a stream which can not seek, feature="async".
+///
+/// `offset` counts the bytes written, and `seek` can not move,
+/// so placeholders can not be reserved. Use `AsyncStreamWriter` to back-patch.
+/// ```
+/// use bin_rs::async_writer::*;
+/// use std::io::Error;
+///
+/// async fn test() -> Result<Vec<u8>, Error> {
+///   let mut writer = AsyncBytesWriter::new(Vec::new());
+///   writer.write_u16_be(0x0102).await?;
+///   writer.write_utf8_string("bin").await?;
+///   writer.flush().await?;
+///   Ok(writer.into_inner()) // [1, 2, b'b', b'i', b'n']
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsyncBytesWriter<W> {
+  core: BytesCore<Tokio<W>>,
+  endian: Endian,
+  placeholders: Placeholders,
+}
+
+impl<W: AsyncWrite + Send + Unpin> AsyncBytesWriter<W> {
+  pub fn new(writer: W) -> AsyncBytesWriter<W> {
+    AsyncBytesWriter {
+      core: BytesCore::new(Tokio(writer), "AsyncBytesWriter"),
+      endian: crate::system_endian(),
+      placeholders: Placeholders::new(),
+    }
+  }
+
+  pub fn get_ref(&self) -> &W {
+    &self.core.get_ref().0
+  }
+
+  /// into_inner returns the inner writer. It does not flush.
+  pub fn into_inner(self) -> W {
+    self.core.into_inner().0
+  }
+}
+
+impl_async_writer!(AsyncBytesWriter, AsyncWrite);
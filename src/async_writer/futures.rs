@@ -0,0 +1,84 @@
+use crate::writer::Placeholders;
+use crate::Endian;
+use futures_io::{AsyncSeek, AsyncWrite};
+use std::io::{Error, SeekFrom};
+
+use super::sink::{impl_async_writer, BytesCore, Futures, StreamCore};
+use super::AsyncBinaryWriter;
+
+/// FuturesBytesWriter is `AsyncBytesWriter` on `futures::io::AsyncWrite`, feature="futures".
+///
+/// `offset` counts the bytes written, and `seek` can not move,
+/// so placeholders can not be reserved. Use `FuturesStreamWriter` to back-patch.
+#[derive(Debug)]
+pub struct FuturesBytesWriter<W> {
+  core: BytesCore<Futures<W>>,
+  endian: Endian,
+  placeholders: Placeholders,
+}
+
+impl<W: AsyncWrite + Send + Unpin> FuturesBytesWriter<W> {
+  pub fn new(writer: W) -> FuturesBytesWriter<W> {
+    FuturesBytesWriter {
+      core: BytesCore::new(Futures(writer), "FuturesBytesWriter"),
+      endian: crate::system_endian(),
+      placeholders: Placeholders::new(),
+    }
+  }
+
+  pub fn get_ref(&self) -> &W {
+    &self.core.get_ref().0
+  }
+
+  /// into_inner returns the inner writer. It does not flush.
+  pub fn into_inner(self) -> W {
+    self.core.into_inner().0
+  }
+}
+
+impl_async_writer!(FuturesBytesWriter, AsyncWrite);
+
+/// FuturesStreamWriter is `AsyncStreamWriter` on `futures::io::AsyncWrite` and `AsyncSeek`,
+/// feature="futures". It can fill placeholders.
+/// ```
+/// use bin_rs::async_writer::*;
+/// use bin_rs::Endian;
+/// use futures_util::io::Cursor;
+/// use std::io::Error;
+///
+/// async fn test() -> Result<Vec<u8>, Error> {
+///   let mut writer = FuturesStreamWriter::new(Cursor::new(Vec::new()));
+///   let size = writer.reserve_u32(Endian::BigEndian).await?;
+///   writer.write_bytes(b"IHDR").await?;
+///   writer.fill_length(&size).await?; // 4
+///   writer.finish().await?;
+///   Ok(writer.into_inner().into_inner())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct FuturesStreamWriter<W> {
+  core: StreamCore<Futures<W>>,
+  endian: Endian,
+  placeholders: Placeholders,
+}
+
+impl<W: AsyncWrite + AsyncSeek + Send + Unpin> FuturesStreamWriter<W> {
+  pub fn new(writer: W) -> FuturesStreamWriter<W> {
+    FuturesStreamWriter {
+      core: StreamCore::new(Futures(writer)),
+      endian: crate::system_endian(),
+      placeholders: Placeholders::new(),
+    }
+  }
+
+  pub fn get_ref(&self) -> &W {
+    &self.core.get_ref().0
+  }
+
+  /// into_inner returns the inner writer. It does not flush.
+  pub fn into_inner(self) -> W {
+    self.core.into_inner().0
+  }
+}
+
+impl_async_writer!(FuturesStreamWriter, AsyncWrite, AsyncSeek);
@@ -0,0 +1,20 @@
+//! 0.0.11 async_writer is the counterpart of async_reader, feature="async" or "futures".
+//!
+//! Methods return `std::io::Error` like `BinaryWriter`.
+//! `ToBinary` is sync only, write a record with `BytesWriter` and send it by `write_bytes`.
+
+mod binary;
+#[cfg(feature = "async")]
+mod bytes;
+#[cfg(feature = "futures")]
+mod futures;
+mod sink;
+#[cfg(feature = "async")]
+mod stream;
+pub use self::binary::AsyncBinaryWriter;
+#[cfg(feature = "async")]
+pub use self::bytes::AsyncBytesWriter;
+#[cfg(feature = "futures")]
+pub use self::futures::{FuturesBytesWriter, FuturesStreamWriter};
+#[cfg(feature = "async")]
+pub use self::stream::AsyncStreamWriter;
@@ -0,0 +1,195 @@
+use crate::error::BinError;
+use std::future::Future;
+use std::io::{Error, SeekFrom};
+
+// Sink is an async stream to write. Tokio and futures-io streams are wrapped in it,
+// so every async writer shares one implementation.
+pub(crate) trait Sink: Send {
+  fn write_all(&mut self, array: &[u8]) -> impl Future<Output = Result<(), Error>> + Send;
+  fn flush(&mut self) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+// SeekSink is a Sink which can seek.
+pub(crate) trait SeekSink: Sink {
+  fn seek(&mut self, seek: SeekFrom) -> impl Future<Output = Result<u64, Error>> + Send;
+}
+
+// Tokio wraps a stream of tokio::io.
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub(crate) struct Tokio<W>(pub(crate) W);
+
+#[cfg(feature = "async")]
+impl<W: tokio::io::AsyncWrite + Send + Unpin> Sink for Tokio<W> {
+  async fn write_all(&mut self, array: &[u8]) -> Result<(), Error> {
+    tokio::io::AsyncWriteExt::write_all(&mut self.0, array).await
+  }
+
+  fn flush(&mut self) -> impl Future<Output = Result<(), Error>> + Send {
+    tokio::io::AsyncWriteExt::flush(&mut self.0)
+  }
+}
+
+#[cfg(feature = "async")]
+impl<W: tokio::io::AsyncWrite + tokio::io::AsyncSeek + Send + Unpin> SeekSink for Tokio<W> {
+  fn seek(&mut self, seek: SeekFrom) -> impl Future<Output = Result<u64, Error>> + Send {
+    tokio::io::AsyncSeekExt::seek(&mut self.0, seek)
+  }
+}
+
+// Futures wraps a stream of futures-io.
+#[cfg(feature = "futures")]
+#[derive(Debug)]
+pub(crate) struct Futures<W>(pub(crate) W);
+
+#[cfg(feature = "futures")]
+impl<W: futures_io::AsyncWrite + Send + Unpin> Sink for Futures<W> {
+  async fn write_all(&mut self, array: &[u8]) -> Result<(), Error> {
+    futures_util::io::AsyncWriteExt::write_all(&mut self.0, array).await
+  }
+
+  fn flush(&mut self) -> impl Future<Output = Result<(), Error>> + Send {
+    futures_util::io::AsyncWriteExt::flush(&mut self.0)
+  }
+}
+
+#[cfg(feature = "futures")]
+impl<W: futures_io::AsyncWrite + futures_io::AsyncSeek + Send + Unpin> SeekSink for Futures<W> {
+  fn seek(&mut self, seek: SeekFrom) -> impl Future<Output = Result<u64, Error>> + Send {
+    futures_util::io::AsyncSeekExt::seek(&mut self.0, seek)
+  }
+}
+
+// BytesCore writes a sink which can not seek, and counts the bytes written.
+// `seek` only accepts the current offset.
+#[derive(Debug)]
+pub(crate) struct BytesCore<S> {
+  sink: S,
+  ptr: u64,
+  name: &'static str,
+}
+
+impl<S: Sink> BytesCore<S> {
+  pub(crate) fn new(sink: S, name: &'static str) -> Self {
+    Self { sink, ptr: 0, name }
+  }
+
+  pub(crate) fn get_ref(&self) -> &S {
+    &self.sink
+  }
+
+  pub(crate) fn into_inner(self) -> S {
+    self.sink
+  }
+
+  pub(crate) async fn write_bytes(&mut self, array: &[u8]) -> Result<(), Error> {
+    self.sink.write_all(array).await?;
+    self.ptr += array.len() as u64;
+    Ok(())
+  }
+
+  pub(crate) async fn offset(&mut self) -> Result<u64, Error> {
+    Ok(self.ptr)
+  }
+
+  pub(crate) async fn seek(&mut self, seek: SeekFrom) -> Result<u64, Error> {
+    let pos = match seek {
+      SeekFrom::Start(pos) => pos as i128,
+      SeekFrom::Current(pos) => self.ptr as i128 + pos as i128,
+      SeekFrom::End(pos) => self.ptr as i128 + pos as i128,
+    };
+    if pos != self.ptr as i128 {
+      let s = format!("{} can not seek to {} from {}", self.name, pos, self.ptr);
+      return Err(BinError::InvalidInput(s).into());
+    }
+    Ok(self.ptr)
+  }
+
+  pub(crate) async fn flush(&mut self) -> Result<(), Error> {
+    self.sink.flush().await
+  }
+
+  pub(crate) fn can_seek(&self) -> bool {
+    false
+  }
+}
+
+// StreamCore writes a sink which can seek.
+#[derive(Debug)]
+pub(crate) struct StreamCore<S> {
+  sink: S,
+}
+
+impl<S: SeekSink> StreamCore<S> {
+  pub(crate) fn new(sink: S) -> Self {
+    Self { sink }
+  }
+
+  pub(crate) fn get_ref(&self) -> &S {
+    &self.sink
+  }
+
+  pub(crate) fn into_inner(self) -> S {
+    self.sink
+  }
+
+  pub(crate) async fn write_bytes(&mut self, array: &[u8]) -> Result<(), Error> {
+    self.sink.write_all(array).await
+  }
+
+  pub(crate) async fn offset(&mut self) -> Result<u64, Error> {
+    self.sink.seek(SeekFrom::Current(0)).await
+  }
+
+  pub(crate) async fn seek(&mut self, seek: SeekFrom) -> Result<u64, Error> {
+    self.sink.seek(seek).await
+  }
+
+  pub(crate) async fn flush(&mut self) -> Result<(), Error> {
+    self.sink.flush().await
+  }
+
+  pub(crate) fn can_seek(&self) -> bool {
+    true
+  }
+}
+
+// impl_async_writer implements AsyncBinaryWriter for `$writer` by its `core` field.
+macro_rules! impl_async_writer {
+  ($writer:ident, $($bound:path),+) => {
+    impl<W: $($bound +)+ Send + Unpin> AsyncBinaryWriter for $writer<W> {
+      fn set_endian(&mut self, endian: Endian) {
+        self.endian = endian;
+      }
+
+      fn endian(&self) -> Endian {
+        self.endian
+      }
+
+      async fn write_bytes(&mut self, array: &[u8]) -> Result<(), Error> {
+        self.core.write_bytes(array).await
+      }
+
+      async fn offset(&mut self) -> Result<u64, Error> {
+        self.core.offset().await
+      }
+
+      async fn seek(&mut self, seek: SeekFrom) -> Result<u64, Error> {
+        self.core.seek(seek).await
+      }
+
+      async fn flush(&mut self) -> Result<(), Error> {
+        self.core.flush().await
+      }
+
+      fn placeholders(&mut self) -> &mut Placeholders {
+        &mut self.placeholders
+      }
+
+      fn can_seek(&self) -> bool {
+        self.core.can_seek()
+      }
+    }
+  };
+}
+pub(crate) use impl_async_writer;
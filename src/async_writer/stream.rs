@@ -0,0 +1,52 @@
+use crate::writer::Placeholders;
+use crate::Endian;
+use std::io::{Error, SeekFrom};
+use tokio::io::{AsyncSeek, AsyncWrite};
+
+use super::sink::{impl_async_writer, StreamCore, Tokio};
+use super::AsyncBinaryWriter;
+
+/// AsyncStreamWriter is an async writer on a seekable stream, feature="async".
+///
+/// It can fill placeholders. Use `AsyncBytesWriter` for sockets.
+/// ```
+/// use bin_rs::async_writer::*;
+/// use bin_rs::Endian;
+/// use std::io::{Cursor, Error};
+///
+/// async fn test() -> Result<Vec<u8>, Error> {
+///   let mut writer = AsyncStreamWriter::new(Cursor::new(Vec::new()));
+///   let size = writer.reserve_u32(Endian::BigEndian).await?;
+///   writer.write_bytes(b"IHDR").await?;
+///   writer.fill_length(&size).await?; // 4
+///   writer.finish().await?;
+///   Ok(writer.into_inner().into_inner())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsyncStreamWriter<W> {
+  core: StreamCore<Tokio<W>>,
+  endian: Endian,
+  placeholders: Placeholders,
+}
+
+impl<W: AsyncWrite + AsyncSeek + Send + Unpin> AsyncStreamWriter<W> {
+  pub fn new(writer: W) -> AsyncStreamWriter<W> {
+    AsyncStreamWriter {
+      core: StreamCore::new(Tokio(writer)),
+      endian: crate::system_endian(),
+      placeholders: Placeholders::new(),
+    }
+  }
+
+  pub fn get_ref(&self) -> &W {
+    &self.core.get_ref().0
+  }
+
+  /// into_inner returns the inner writer. It does not flush.
+  pub fn into_inner(self) -> W {
+    self.core.into_inner().0
+  }
+}
+
+impl_async_writer!(AsyncStreamWriter, AsyncWrite, AsyncSeek);
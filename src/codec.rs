@@ -0,0 +1,216 @@
+use crate::error::Malformed;
+use crate::reader::{CodeType, DecodeOptions, Trim};
+use crate::writer::Unmappable;
+#[cfg(feature = "codec")]
+use encoding_rs::EncoderResult;
+
+/// decode decodes `bytes` as `code`. It returns None if bytes are malformed in strict mode.
+/// A leading BOM of `code` is removed, like `EncodeOptions::bom` writes it.
+/// Without feature="codec", only US-ASCII, UTF-8 and UTF-16 are decoded, see `CodeType::is_supported`.
+pub(crate) fn decode(bytes: &[u8], code: CodeType, options: DecodeOptions) -> Option<String> {
+  let bytes = strip_bom(bytes, code);
+  let bytes = if options.nul_terminated {
+    terminate(bytes, code)
+  } else {
+    bytes
+  };
+  match code {
+    CodeType::Ascii => Some(bytes.iter().map(|b| *b as char).collect()),
+    CodeType::Utf8 if options.lossy => Some(String::from_utf8_lossy(bytes).into()),
+    CodeType::Utf8 => String::from_utf8(bytes.to_vec()).ok(),
+    CodeType::Utf16Be | CodeType::Utf16le => {
+      let chunks = bytes.chunks_exact(2);
+      let odd = !chunks.remainder().is_empty();
+      let units: Vec<u16> = match code {
+        CodeType::Utf16Be => chunks.map(|c| u16::from_be_bytes([c[0], c[1]])).collect(),
+        _ => chunks.map(|c| u16::from_le_bytes([c[0], c[1]])).collect(),
+      };
+      if options.lossy {
+        let mut string = String::from_utf16_lossy(&units);
+        if odd {
+          string.push('\u{FFFD}');
+        }
+        Some(string)
+      } else if odd {
+        None
+      } else {
+        String::from_utf16(&units).ok()
+      }
+    }
+    _ => decode_legacy(bytes, code, options.lossy),
+  }
+}
+
+/// decode_local decodes `size` bytes read as `code`, or fails over them.
+pub(crate) fn decode_local(
+  bytes: &[u8],
+  code: CodeType,
+  options: DecodeOptions,
+) -> Result<String, Malformed> {
+  decode(bytes, code, options).ok_or(Malformed::InvalidString {
+    len: bytes.len(),
+    encoding: code.name(),
+  })
+}
+
+/// decode_fixed decodes a field of `bytes` padded as `trim`.
+pub(crate) fn decode_fixed(bytes: &[u8], code: CodeType, trim: Trim) -> Result<String, Malformed> {
+  let options = DecodeOptions {
+    nul_terminated: matches!(trim, Trim::Nul | Trim::Both),
+    lossy: false,
+  };
+  let string = decode_local(bytes, code, options)?;
+  match trim {
+    Trim::Space | Trim::Both => Ok(string.trim_end_matches(' ').to_string()),
+    Trim::Nul | Trim::None => Ok(string),
+  }
+}
+
+/// decode_utf8 decodes `array` from the last `len` bytes read.
+pub(crate) fn decode_utf8(array: Vec<u8>, len: usize) -> Result<String, Malformed> {
+  String::from_utf8(array).map_err(|_| Malformed::InvalidString {
+    len,
+    encoding: "UTF-8",
+  })
+}
+
+/// decode_cstring decodes `array` of read_until(0) without NUL.
+pub(crate) fn decode_cstring(mut array: Vec<u8>) -> Result<String, Malformed> {
+  let len = array.len();
+  if array.pop() != Some(0) {
+    return Err(Malformed::Unterminated { len });
+  }
+  decode_utf8(array, len)
+}
+
+/// line_bytes removes "\n" or "\r\n" from `array` of read_until(b'\n').
+/// It returns None for no bytes, the end of data.
+pub(crate) fn line_bytes(mut array: Vec<u8>) -> Option<Vec<u8>> {
+  if array.is_empty() {
+    return None;
+  }
+  if array.last() == Some(&b'\n') {
+    array.pop();
+    if array.last() == Some(&b'\r') {
+      array.pop();
+    }
+  }
+  Some(array)
+}
+
+/// decode_line decodes a utf8 line of `array` of read_until(b'\n').
+pub(crate) fn decode_line(array: Vec<u8>) -> Result<Option<String>, Malformed> {
+  let len = array.len();
+  line_bytes(array)
+    .map(|line| decode_utf8(line, len))
+    .transpose()
+}
+
+#[cfg(feature = "codec")]
+fn decode_legacy(bytes: &[u8], code: CodeType, lossy: bool) -> Option<String> {
+  let encoding = code.encoding()?;
+  if lossy {
+    Some(encoding.decode_without_bom_handling(bytes).0.into())
+  } else {
+    encoding
+      .decode_without_bom_handling_and_without_replacement(bytes)
+      .map(|s| s.into())
+  }
+}
+
+#[cfg(not(feature = "codec"))]
+fn decode_legacy(_: &[u8], _: CodeType, _: bool) -> Option<String> {
+  None
+}
+
+fn strip_bom(bytes: &[u8], code: CodeType) -> &[u8] {
+  let bom: &[u8] = match code {
+    CodeType::Utf8 => b"\xef\xbb\xbf",
+    CodeType::Utf16Be => b"\xfe\xff",
+    CodeType::Utf16le => b"\xff\xfe",
+    _ => return bytes,
+  };
+  bytes.strip_prefix(bom).unwrap_or(bytes)
+}
+
+fn terminate(bytes: &[u8], code: CodeType) -> &[u8] {
+  let end = match code {
+    CodeType::Utf16Be | CodeType::Utf16le => bytes
+      .chunks_exact(2)
+      .position(|c| c == [0, 0])
+      .map(|i| i * 2),
+    _ => bytes.iter().position(|b| *b == 0),
+  };
+  &bytes[..end.unwrap_or(bytes.len())]
+}
+
+/// encode encodes `string` as `code` without BOM and NUL.
+/// An unmappable character is returned with the byte position where it would be written.
+pub(crate) fn encode(
+  string: &str,
+  code: CodeType,
+  unmappable: Unmappable,
+) -> Result<Vec<u8>, (char, usize)> {
+  match code {
+    CodeType::Utf16Be => Ok(string.encode_utf16().flat_map(u16::to_be_bytes).collect()),
+    CodeType::Utf16le => Ok(string.encode_utf16().flat_map(u16::to_le_bytes).collect()),
+    CodeType::Utf8 => Ok(string.as_bytes().to_vec()),
+    CodeType::Ascii => {
+      let mut bytes = Vec::with_capacity(string.len());
+      for c in string.chars() {
+        match (c as u32 <= 0xff, unmappable) {
+          (true, _) => bytes.push(c as u8),
+          (false, Unmappable::Replace) => bytes.push(b'?'),
+          (false, Unmappable::Error) => return Err((c, bytes.len())),
+        }
+      }
+      Ok(bytes)
+    }
+    _ => encode_legacy(string, code, unmappable),
+  }
+}
+
+#[cfg(feature = "codec")]
+fn encode_legacy(
+  string: &str,
+  code: CodeType,
+  unmappable: Unmappable,
+) -> Result<Vec<u8>, (char, usize)> {
+  let mut bytes = Vec::with_capacity(string.len());
+  let mut encoder = match code.encoding() {
+    Some(encoding) => encoding.new_encoder(),
+    None => return Ok(bytes),
+  };
+  let mut src = string;
+  loop {
+    reserve(&mut bytes, &encoder, src.len());
+    let (result, read) = encoder.encode_from_utf8_to_vec_without_replacement(src, &mut bytes, true);
+    src = &src[read..];
+    match result {
+      EncoderResult::InputEmpty => return Ok(bytes),
+      EncoderResult::OutputFull => {}
+      EncoderResult::Unmappable(c) => match unmappable {
+        Unmappable::Replace => {
+          // '?' goes through the encoder, ISO-2022-JP has to return to ASCII.
+          // it is always mappable.
+          reserve(&mut bytes, &encoder, 1);
+          let _ = encoder.encode_from_utf8_to_vec_without_replacement("?", &mut bytes, false);
+        }
+        Unmappable::Error => return Err((c, bytes.len())),
+      },
+    }
+  }
+}
+
+#[cfg(not(feature = "codec"))]
+fn encode_legacy(_: &str, _: CodeType, _: Unmappable) -> Result<Vec<u8>, (char, usize)> {
+  Ok(Vec::new())
+}
+
+#[cfg(feature = "codec")]
+fn reserve(bytes: &mut Vec<u8>, encoder: &encoding_rs::Encoder, len: usize) {
+  let additional = encoder
+    .max_buffer_length_from_utf8_without_replacement(len)
+    .unwrap_or(len);
+  bytes.reserve(additional);
+}
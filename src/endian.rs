@@ -43,8 +43,17 @@ impl Display for Endian {
   }
 }
 
+#[derive(Copy, Debug, Clone, PartialEq)]
+/// BitOrder is the order of bits in a byte for bit streams.
+pub enum BitOrder {
+  /// The most significant bit comes first (ex. JPEG, H.264, MPEG)
+  MsbFirst,
+  /// The least significant bit comes first (ex. DEFLATE, GIF LZW)
+  LsbFirst,
+}
+
 pub(crate) fn system_endian() -> Endian {
-  if cfg!(tarread_endian = "big") {
+  if cfg!(target_endian = "big") {
     Endian::BigEndian
   } else {
     Endian::LittleEndian
@@ -1,22 +1,274 @@
-use std::error::Error;
-use std::fmt;
-/// 0.0.9
-
-
-#[derive(Debug)]
-pub struct BinError {
-    pub(crate) side: &'static &'static str,
-    pub(crate) message: Box<String>,
-}
-
-impl fmt::Display for BinError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}:{}", self.side,self.message)
-    }
-}
-
-impl Error for BinError {
-    fn source(&self) -> Option<&(dyn Error + 'static)> {
-        Some(self)
-    }
-}
\ No newline at end of file
+//! 0.0.11 BinError is the error of this crate.
+//!
+//! Readers and writers still return `std::io::Error`, which wraps a `BinError`.
+//! Convert it back with `BinError::from` to match the failure.
+//! ```
+//! use bin_rs::error::BinError;
+//! use bin_rs::reader::*;
+//!
+//! let mut reader = BytesReader::new(b"\x01\x02\x03");
+//! reader.skip_ptr(1).unwrap();
+//! let err = reader.read_u32_be().unwrap_err();
+//! match BinError::from(err) {
+//!   BinError::UnexpectedEof { offset, requested, available } => {
+//!     assert_eq!((offset, requested, available), (1, 4, 2));
+//!   }
+//!   e => panic!("{}", e),
+//! }
+//! ```
+use std::error::Error;
+use std::fmt;
+use std::io::{self, ErrorKind};
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum BinError {
+  /// The source has only `available` bytes at `offset`, but `requested` bytes are read.
+  UnexpectedEof {
+    offset: u64,
+    requested: usize,
+    available: usize,
+  },
+  /// A read at `offset` runs out of data in incremental mode, at least `needed` more bytes are required.
+  /// Append them and read again. See `BytesReader::set_incremental`.
+  Incomplete { offset: u64, needed: usize },
+  /// Bytes from `offset` are not a valid string in `encoding`.
+  InvalidString { offset: u64, encoding: &'static str },
+  /// A magic number at `offset` does not match.
+  BadMagic {
+    offset: u64,
+    expected: Vec<u8>,
+    found: Vec<u8>,
+  },
+  /// A seek to `position` is outside of the source of `length` bytes.
+  SeekOutOfRange { position: i128, length: u64 },
+  /// A value at `offset` does not fit in its type or field.
+  Overflow { offset: u64, message: String },
+  /// A value at `offset` is not valid, for example a bool which is not 0 or 1.
+  InvalidData { offset: u64, message: String },
+  /// `character` at `offset` can not be written in `encoding`.
+  UnmappableCharacter {
+    offset: u64,
+    encoding: &'static str,
+    character: char,
+  },
+  /// Placeholders at `offsets` are not filled.
+  UnfilledPlaceholders { offsets: Vec<u64> },
+  /// A function is called with a wrong argument.
+  InvalidInput(String),
+  /// An error from the underlying io.
+  Io(io::Error),
+  /// `source` occurs in `label`, which began at `offset`. Made by `BinaryReader::with_context`.
+  Context {
+    label: String,
+    offset: u64,
+    source: Box<BinError>,
+  },
+}
+
+impl BinError {
+  /// kind returns the `ErrorKind` used when this error is converted to `io::Error`.
+  pub fn kind(&self) -> ErrorKind {
+    match self {
+      Self::UnexpectedEof { .. } => ErrorKind::UnexpectedEof,
+      Self::Incomplete { .. } => ErrorKind::WouldBlock,
+      Self::InvalidString { .. }
+      | Self::BadMagic { .. }
+      | Self::Overflow { .. }
+      | Self::InvalidData { .. } => ErrorKind::InvalidData,
+      Self::SeekOutOfRange { .. } | Self::UnmappableCharacter { .. } | Self::InvalidInput(_) => {
+        ErrorKind::InvalidInput
+      }
+      Self::UnfilledPlaceholders { .. } => ErrorKind::Other,
+      Self::Io(e) => e.kind(),
+      Self::Context { source, .. } => source.kind(),
+    }
+  }
+
+  /// offset returns the offset where the error occurs, if it is known.
+  pub fn offset(&self) -> Option<u64> {
+    match self {
+      Self::Context { source, .. } => source.offset(),
+      Self::UnexpectedEof { offset, .. }
+      | Self::Incomplete { offset, .. }
+      | Self::InvalidString { offset, .. }
+      | Self::BadMagic { offset, .. }
+      | Self::Overflow { offset, .. }
+      | Self::InvalidData { offset, .. }
+      | Self::UnmappableCharacter { offset, .. } => Some(*offset),
+      _ => None,
+    }
+  }
+
+  /// context wraps this error with `label` which began at `offset`.
+  pub fn context(self, label: impl Into<String>, offset: u64) -> Self {
+    Self::Context {
+      label: label.into(),
+      offset,
+      source: Box::new(self),
+    }
+  }
+
+  /// contexts returns labels and their offsets, from the outermost.
+  pub fn contexts(&self) -> Vec<(&str, u64)> {
+    let mut contexts = Vec::new();
+    let mut err = self;
+    while let Self::Context {
+      label,
+      offset,
+      source,
+    } = err
+    {
+      contexts.push((label.as_str(), *offset));
+      err = source;
+    }
+    contexts
+  }
+
+  /// root returns the error without contexts.
+  pub fn root(&self) -> &BinError {
+    match self {
+      Self::Context { source, .. } => source.root(),
+      err => err,
+    }
+  }
+}
+
+impl fmt::Display for BinError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::UnexpectedEof {
+        offset,
+        requested,
+        available,
+      } => write!(
+        f,
+        "unexpected eof at {}: requested {} bytes, but {} bytes available",
+        offset, requested, available
+      ),
+      Self::Incomplete { offset, needed } => write!(
+        f,
+        "incomplete data at {}: {} more bytes needed",
+        offset, needed
+      ),
+      Self::InvalidString { offset, encoding } => {
+        write!(f, "invalid {} string at {}", encoding, offset)
+      }
+      Self::BadMagic {
+        offset,
+        expected,
+        found,
+      } => write!(
+        f,
+        "bad magic at {}: expected {:?}, found {:?}",
+        offset, expected, found
+      ),
+      Self::SeekOutOfRange { position, length } => write!(
+        f,
+        "seek to {} is out of range, length is {}",
+        position, length
+      ),
+      Self::Overflow { offset, message } => write!(f, "overflow at {}: {}", offset, message),
+      Self::InvalidData { offset, message } => {
+        write!(f, "invalid data at {}: {}", offset, message)
+      }
+      Self::UnmappableCharacter {
+        offset,
+        encoding,
+        character,
+      } => write!(
+        f,
+        "{:?} at {} can not be written in {}",
+        character, offset, encoding
+      ),
+      Self::UnfilledPlaceholders { offsets } => {
+        write!(f, "placeholders at {:?} are not filled", offsets)
+      }
+      Self::InvalidInput(message) => write!(f, "{}", message),
+      Self::Io(e) => write!(f, "{}", e),
+      Self::Context {
+        label,
+        offset,
+        source,
+      } => write!(f, "{} (at {}): {}", label, offset, source),
+    }
+  }
+}
+
+impl Error for BinError {
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    match self {
+      Self::Io(e) => Some(e),
+      // the message of a context already contains its source
+      Self::Context { source, .. } => source.source(),
+      _ => None,
+    }
+  }
+}
+
+impl From<BinError> for io::Error {
+  fn from(err: BinError) -> Self {
+    match err {
+      BinError::Io(e) => e,
+      err => io::Error::new(err.kind(), err),
+    }
+  }
+}
+
+/// A `BinError` wrapped by `io::Error` is unwrapped, others become `BinError::Io`.
+impl From<io::Error> for BinError {
+  fn from(err: io::Error) -> Self {
+    if err.get_ref().is_some_and(|e| e.is::<BinError>()) {
+      match err.into_inner().map(|e| e.downcast::<BinError>()) {
+        Some(Ok(e)) => *e,
+        _ => unreachable!(),
+      }
+    } else {
+      BinError::Io(err)
+    }
+  }
+}
+
+// Malformed is a value which failed to decode from the last `len` bytes read.
+// Readers make it a BinError at the offset where the value starts.
+#[derive(Debug)]
+pub(crate) enum Malformed {
+  Overflow { len: usize, message: &'static str },
+  InvalidData { len: usize, message: &'static str },
+  InvalidString { len: usize, encoding: &'static str },
+  // data ended before the terminator
+  Unterminated { len: usize },
+}
+
+impl Malformed {
+  pub(crate) fn len(&self) -> usize {
+    match self {
+      Self::Overflow { len, .. }
+      | Self::InvalidData { len, .. }
+      | Self::InvalidString { len, .. }
+      | Self::Unterminated { len } => *len,
+    }
+  }
+
+  // error returns the error of the value starting at `offset`.
+  pub(crate) fn error(self, offset: u64) -> io::Error {
+    let err = match self {
+      Self::Overflow { message, .. } => BinError::Overflow {
+        offset,
+        message: message.to_string(),
+      },
+      Self::InvalidData { message, .. } => BinError::InvalidData {
+        offset,
+        message: message.to_string(),
+      },
+      Self::InvalidString { encoding, .. } => BinError::InvalidString { offset, encoding },
+      // the terminator is missing at the end of data
+      Self::Unterminated { len } => BinError::UnexpectedEof {
+        offset: offset + len as u64,
+        requested: 1,
+        available: 0,
+      },
+    };
+    err.into()
+  }
+}
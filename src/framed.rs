@@ -0,0 +1,202 @@
+//! 0.0.11 framed makes tokio-util `Decoder` and `Encoder` from parse functions, feature="framed".
+//!
+//! A decode function reads one frame from a `SliceReader` over the buffered bytes,
+//! in incremental mode (see `SliceReader::set_incremental`).
+//! If it runs out of the buffered bytes (`BinError::Incomplete`), the frame is partial,
+//! so `decode` returns `Ok(None)` and waits for more bytes without consuming them.
+//! It includes `read_until`, `read_line` and `read_cstring` which have not found the delimiter yet.
+//! An `UnexpectedEof`, for example an overrun of a `SubReader` in a complete frame,
+//! is `BinError::InvalidData`.
+//! Report a malformed frame with another error, for example `BinError::InvalidData`.
+//! An encode function writes one frame to a `BytesWriter`.
+//!
+//! The decode function takes a `SliceReader`, not a `BytesReader`, because `decode` runs again
+//! on every arrival of bytes, and a `BytesReader` would copy the whole buffer each time.
+//! A parser for `BytesReader` is shared by making it generic on `BinaryReader`,
+//! see the second example.
+//! ```
+//! use bin_rs::framed::BinCodec;
+//! use bin_rs::reader::*;
+//! use bin_rs::writer::*;
+//! use bin_rs::Endian;
+//! use tokio_util::bytes::BytesMut;
+//! use tokio_util::codec::{Decoder, Encoder};
+//!
+//! // a frame is u16 length and utf8 text
+//! let mut codec = BinCodec::new(
+//!   |reader: &mut SliceReader| {
+//!     let len = reader.read_u16()? as usize;
+//!     reader.read_utf8_string(len)
+//!   },
+//!   |text: String, writer: &mut BytesWriter| {
+//!     writer.write_u16(text.len() as u16)?;
+//!     writer.write_utf8_string(&text)?;
+//!     Ok(())
+//!   },
+//! );
+//! codec.set_endian(Endian::BigEndian);
+//!
+//! let mut buffer = BytesMut::new();
+//! codec.encode("hello".to_string(), &mut buffer).unwrap();
+//! assert_eq!(&buffer[..], b"\x00\x05hello");
+//!
+//! let mut partial = buffer.split_to(4);
+//! assert_eq!(codec.decode(&mut partial).unwrap(), None);
+//! partial.unsplit(buffer);
+//! assert_eq!(codec.decode(&mut partial).unwrap(), Some("hello".to_string()));
+//! assert!(partial.is_empty());
+//! ```
+//! A generic parser runs on a `BytesReader` and in a codec.
+//! ```
+//! use bin_rs::framed::BinCodec;
+//! use bin_rs::reader::*;
+//! use std::io::Error;
+//! use tokio_util::bytes::BytesMut;
+//! use tokio_util::codec::Decoder;
+//!
+//! fn parse_line<R: BinaryReader>(reader: &mut R) -> Result<String, Error> {
+//!   Ok(reader.read_line()?.unwrap_or_default())
+//! }
+//!
+//! let mut reader = BytesReader::new(b"hello\n");
+//! assert_eq!(parse_line(&mut reader).unwrap(), "hello");
+//!
+//! let mut codec = BinCodec::decoder(|reader: &mut SliceReader| parse_line(reader));
+//! let mut buffer = BytesMut::from(&b"hel"[..]);
+//! assert_eq!(codec.decode(&mut buffer).unwrap(), None);
+//! buffer.extend_from_slice(b"lo\n");
+//! assert_eq!(codec.decode(&mut buffer).unwrap(), Some("hello".to_string()));
+//! ```
+use crate::error::BinError;
+use crate::reader::{BinaryReader, SliceReader};
+use crate::writer::{BinaryWriter, BytesWriter};
+use crate::Endian;
+use std::io::{Error, ErrorKind};
+use tokio_util::bytes::{Buf, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+/// BinCodec is a `Decoder` and `Encoder` made by functions.
+///
+/// `decoder` makes a decode only codec for `FramedRead`,
+/// `encoder` makes an encode only codec for `FramedWrite`, and `new` makes both for `Framed`.
+#[derive(Debug, Clone)]
+pub struct BinCodec<D, E> {
+  decode: D,
+  encode: E,
+  endian: Endian,
+  max_length: usize,
+}
+
+impl<D> BinCodec<D, ()> {
+  pub fn decoder<T>(decode: D) -> Self
+  where
+    D: FnMut(&mut SliceReader<'_>) -> Result<T, Error>,
+  {
+    Self::new(decode, ())
+  }
+}
+
+impl<E> BinCodec<(), E> {
+  pub fn encoder<T>(encode: E) -> Self
+  where
+    E: FnMut(T, &mut BytesWriter) -> Result<(), Error>,
+  {
+    Self::new((), encode)
+  }
+}
+
+impl<D, E> BinCodec<D, E> {
+  pub fn new(decode: D, encode: E) -> Self {
+    Self {
+      decode,
+      encode,
+      endian: crate::system_endian(),
+      max_length: usize::MAX,
+    }
+  }
+
+  /// set_endian sets the endian of readers and writers given to the functions.
+  pub fn set_endian(&mut self, endian: Endian) {
+    self.endian = endian;
+  }
+
+  pub fn endian(&self) -> Endian {
+    self.endian
+  }
+
+  /// set_max_length limits the bytes buffered for a partial frame.
+  /// A longer partial frame is an error, default is no limit.
+  pub fn set_max_length(&mut self, max_length: usize) {
+    self.max_length = max_length;
+  }
+
+  pub fn max_length(&self) -> usize {
+    self.max_length
+  }
+}
+
+impl<D, E, T> Decoder for BinCodec<D, E>
+where
+  D: FnMut(&mut SliceReader<'_>) -> Result<T, Error>,
+{
+  type Item = T;
+  type Error = Error;
+
+  fn decode(&mut self, src: &mut BytesMut) -> Result<Option<T>, Error> {
+    if src.is_empty() {
+      return Ok(None);
+    }
+    let mut reader = SliceReader::new(src);
+    reader.set_endian(self.endian);
+    reader.set_incremental(true);
+    match (self.decode)(&mut reader) {
+      Ok(item) => {
+        let len = reader.offset()? as usize;
+        if len == 0 {
+          let message = "decode function consumed no bytes".to_string();
+          return Err(BinError::InvalidData { offset: 0, message }.into());
+        }
+        src.advance(len);
+        Ok(Some(item))
+      }
+      Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::UnexpectedEof) => {
+        let err = BinError::from(e);
+        let needed = match err.root() {
+          BinError::Incomplete { needed, .. } => *needed,
+          BinError::UnexpectedEof { .. } => {
+            let message = format!("malformed frame: {}", err);
+            return Err(BinError::InvalidData { offset: 0, message }.into());
+          }
+          _ => return Err(err.into()),
+        };
+        if src.len() >= self.max_length {
+          let message = format!(
+            "partial frame of {} bytes reaches max length {}",
+            src.len(),
+            self.max_length
+          );
+          return Err(BinError::InvalidData { offset: 0, message }.into());
+        }
+        src.reserve(needed);
+        Ok(None)
+      }
+      Err(e) => Err(e),
+    }
+  }
+}
+
+impl<D, E, T> Encoder<T> for BinCodec<D, E>
+where
+  E: FnMut(T, &mut BytesWriter) -> Result<(), Error>,
+{
+  type Error = Error;
+
+  fn encode(&mut self, item: T, dst: &mut BytesMut) -> Result<(), Error> {
+    let mut writer = BytesWriter::new();
+    writer.set_endian(self.endian);
+    (self.encode)(item, &mut writer)?;
+    writer.finish()?;
+    dst.extend_from_slice(writer.as_slice());
+    Ok(())
+  }
+}
@@ -5,6 +5,7 @@
 //! use feature="util"
 //!
 //! 0.0.9 utf16 string read/write
+//! 0.0.11 LEB128, zigzag and VLQ writers
 
 use crate::Endian;
 
@@ -40,7 +41,7 @@ pub fn read_u16_be(buf: &[u8], ptr: usize) -> u16 {
 #[allow(unused)]
 #[inline]
 pub fn read_i16_be(buf: &[u8], ptr: usize) -> i16 {
-  (((buf[ptr] as u16) << 8 | (buf[ptr + 1] as u16)) as u16) as i16
+  ((buf[ptr] as u16) << 8 | (buf[ptr + 1] as u16)) as i16
 }
 
 #[allow(unused)]
@@ -55,10 +56,10 @@ pub fn read_u32_be(buf: &[u8], ptr: usize) -> u32 {
 #[allow(unused)]
 #[inline]
 pub fn read_i32_be(buf: &[u8], ptr: usize) -> i32 {
-  (((buf[ptr] as u32) << 24
+  ((buf[ptr] as u32) << 24
     | (buf[ptr + 1] as u32) << 16
     | (buf[ptr + 2] as u32) << 8
-    | (buf[ptr + 3] as u32)) as u32) as i32
+    | (buf[ptr + 3] as u32)) as i32
 }
 
 #[allow(unused)]
@@ -77,14 +78,14 @@ pub fn read_u64_be(buf: &[u8], ptr: usize) -> u64 {
 #[allow(unused)]
 #[inline]
 pub fn read_i64_be(buf: &[u8], ptr: usize) -> i64 {
-  (((buf[ptr] as u64) << 56
+  ((buf[ptr] as u64) << 56
     | (buf[ptr + 1] as u64) << 48
     | (buf[ptr + 2] as u64) << 40
     | (buf[ptr + 3] as u64) << 32
     | (buf[ptr + 4] as u64) << 24
     | (buf[ptr + 5] as u64) << 16
     | (buf[ptr + 6] as u64) << 8
-    | (buf[ptr + 7] as u64)) as u64) as i64
+    | (buf[ptr + 7] as u64)) as i64
 }
 
 #[allow(unused)]
@@ -133,14 +134,14 @@ pub fn read_u64_le(buf: &[u8], ptr: usize) -> u64 {
 #[allow(unused)]
 #[inline]
 pub fn read_i64_le(buf: &[u8], ptr: usize) -> i64 {
-  (((buf[ptr + 7] as u64) << 56
+  ((buf[ptr + 7] as u64) << 56
     | (buf[ptr + 6] as u64) << 48
     | (buf[ptr + 5] as u64) << 40
     | (buf[ptr + 4] as u64) << 32
     | (buf[ptr + 3] as u64) << 24
     | (buf[ptr + 2] as u64) << 16
     | (buf[ptr + 1] as u64) << 8
-    | buf[ptr] as u64) as u64) as i64
+    | buf[ptr] as u64) as i64
 }
 
 #[allow(unused)]
@@ -260,7 +261,7 @@ pub fn read_ascii_string(buf: &[u8], _ptr: usize, _num: usize) -> String {
   for b in buf {
     u16s.push(*b as u16);
   }
-  return String::from_utf16_lossy(&u16s);
+  String::from_utf16_lossy(&u16s)
 }
 
 #[allow(unused)]
@@ -275,12 +276,8 @@ pub fn read_string(buf: &[u8], ptr: usize, num: usize) -> String {
   }
   let res = String::from_utf8(s);
   match res {
-    Ok(strings) => {
-      return strings;
-    }
-    _ => {
-      return "".to_string();
-    }
+    Ok(strings) => strings,
+    _ => "".to_string(),
   }
 }
 
@@ -558,7 +555,7 @@ pub fn write_i128_le(num: i128, buf: &mut Vec<u8>) {
 
 pub fn write_ascii_string(srting: String, buf: &mut Vec<u8>) {
   let bytes = srting.as_bytes();
-  for b in bytes.into_iter() {
+  for b in bytes.iter() {
     buf.push(*b);
   }
   buf.push(0)
@@ -568,7 +565,7 @@ pub fn write_ascii_string(srting: String, buf: &mut Vec<u8>) {
 #[inline]
 pub fn write_string(srting: String, buf: &mut Vec<u8>) {
   let bytes = srting.as_bytes();
-  for b in bytes.into_iter() {
+  for b in bytes.iter() {
     buf.push(*b);
   }
 }
@@ -576,7 +573,7 @@ pub fn write_string(srting: String, buf: &mut Vec<u8>) {
 #[allow(unused)]
 #[inline]
 pub fn write_bytes(bytes: &[u8], buf: &mut Vec<u8>) {
-  for b in bytes.into_iter() {
+  for b in bytes.iter() {
     buf.push(*b);
   }
 }
@@ -636,3 +633,31 @@ pub fn write_f64_le(num: f64, buf: &mut Vec<u8>) {
     buf.push(b);
   }
 }
+
+/// write_uleb128 writes unsigned LEB128 in the shortest form.
+#[allow(unused)]
+#[inline]
+pub fn write_uleb128(num: u64, buf: &mut Vec<u8>) {
+  crate::varint::encode_uleb128(num, buf)
+}
+
+/// write_sleb128 writes signed LEB128 in the shortest form.
+#[allow(unused)]
+#[inline]
+pub fn write_sleb128(num: i64, buf: &mut Vec<u8>) {
+  crate::varint::encode_sleb128(num, buf)
+}
+
+/// write_zigzag_varint writes protobuf style sint64.
+#[allow(unused)]
+#[inline]
+pub fn write_zigzag_varint(num: i64, buf: &mut Vec<u8>) {
+  crate::varint::encode_zigzag_varint(num, buf)
+}
+
+/// write_vlq writes a big-endian variable-length quantity (MIDI).
+#[allow(unused)]
+#[inline]
+pub fn write_vlq(num: u64, buf: &mut Vec<u8>) {
+  crate::varint::encode_vlq(num, buf)
+}
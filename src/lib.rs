@@ -1,12 +1,25 @@
 //! Crate bin_rs is a binary manipulate crate.
 //!
 
-pub mod reader;
-#[cfg(feature = "async")]
-pub use async_reader;
+// generated code of bin-rs-derive refers ::bin_rs
+extern crate self as bin_rs;
+
+#[cfg(any(feature = "async", feature = "futures"))]
+pub mod async_reader;
+#[cfg(any(feature = "async", feature = "futures"))]
+pub mod async_writer;
+mod codec;
 pub mod endian;
+pub mod error;
+#[cfg(feature = "framed")]
+pub mod framed;
 #[cfg(feature = "util")]
 pub mod io;
+pub mod reader;
+pub mod writer;
+#[cfg(feature = "derive")]
+pub use bin_rs_derive::{BinRead, BinWrite};
 pub use endian::*;
 #[cfg(test)]
 pub mod test;
+mod varint;
@@ -1,13 +1,30 @@
+use crate::error::{BinError, Malformed};
+use crate::varint::{decode_zigzag, Sleb128, Uleb128, Varint, Vlq};
 use crate::Endian;
 use std::io::{Error, ErrorKind, SeekFrom};
 
+#[cfg(feature = "codec")]
+use super::DecodeOptions;
+use super::{CodeType, FromBinary, LengthPrefix, SubReader, Trim};
+
 /// 0.0.11 Some functions have been changed to be written in this trait.
+///
+/// An implementation only has to provide `read_exact`, `read_bytes_no_move`,
+/// `skip_ptr`, `offset`, `seek` and endian handling. Numbers are read through `read_exact`
+/// unless the implementation overrides them.
 pub trait BinaryReader {
   fn set_endian(&mut self, endian: Endian);
   fn endian(&self) -> Endian;
 
-  fn read_byte(&mut self) -> Result<u8, Error>;
-  fn read_u8(&mut self) -> Result<u8, Error>;
+  fn read_byte(&mut self) -> Result<u8, Error> {
+    let mut array = [0; 1];
+    self.read_exact(&mut array)?;
+    Ok(array[0])
+  }
+
+  fn read_u8(&mut self) -> Result<u8, Error> {
+    self.read_byte()
+  }
 
   #[deprecated(since = "0.0.10", note = "Use new function `read_exact()` instead")]
   fn read_bytes(&mut self, array: &mut [u8]) -> Result<(), Error> {
@@ -17,51 +34,317 @@ pub trait BinaryReader {
   fn read_exact(&mut self, array: &mut [u8]) -> Result<(), Error>;
 
   //    fn read_bytes(&mut self,len: usize) -> Result<&[u8],Error>;
-  fn read_bytes_as_vec(&mut self, len: usize) -> Result<Vec<u8>, Error>;
+  fn read_bytes_as_vec(&mut self, len: usize) -> Result<Vec<u8>, Error> {
+    let mut array = vec![0; len];
+    self.read_exact(&mut array)?;
+    Ok(array)
+  }
 
   /// read_bytes_no_move does not move offset after read_bytes.
   ///
   /// Assumed to be used for header checks.
   ///
-
   fn read_bytes_no_move(&mut self, len: usize) -> Result<Vec<u8>, Error>;
 
-  fn read_u16(&mut self) -> Result<u16, Error>;
-  fn read_u32(&mut self) -> Result<u32, Error>;
-  fn read_u64(&mut self) -> Result<u64, Error>;
-  fn read_u128(&mut self) -> Result<u128, Error>;
-  fn read_i8(&mut self) -> Result<i8, Error>;
-  fn read_i16(&mut self) -> Result<i16, Error>;
-  fn read_i32(&mut self) -> Result<i32, Error>;
-  fn read_i64(&mut self) -> Result<i64, Error>;
-  fn read_i128(&mut self) -> Result<i128, Error>;
-
-  fn read_f32(&mut self) -> Result<f32, Error>;
-  fn read_f64(&mut self) -> Result<f64, Error>;
-
-  fn read_u16_be(&mut self) -> Result<u16, Error>;
-  fn read_u32_be(&mut self) -> Result<u32, Error>;
-  fn read_u64_be(&mut self) -> Result<u64, Error>;
-  fn read_u128_be(&mut self) -> Result<u128, Error>;
-  fn read_i16_be(&mut self) -> Result<i16, Error>;
-  fn read_i32_be(&mut self) -> Result<i32, Error>;
-  fn read_i64_be(&mut self) -> Result<i64, Error>;
-  fn read_i128_be(&mut self) -> Result<i128, Error>;
-
-  fn read_f32_be(&mut self) -> Result<f32, Error>;
-  fn read_f64_be(&mut self) -> Result<f64, Error>;
-
-  fn read_u16_le(&mut self) -> Result<u16, Error>;
-  fn read_u32_le(&mut self) -> Result<u32, Error>;
-  fn read_u64_le(&mut self) -> Result<u64, Error>;
-  fn read_u128_le(&mut self) -> Result<u128, Error>;
-  fn read_i16_le(&mut self) -> Result<i16, Error>;
-  fn read_i32_le(&mut self) -> Result<i32, Error>;
-  fn read_i64_le(&mut self) -> Result<i64, Error>;
-  fn read_i128_le(&mut self) -> Result<i128, Error>;
-
-  fn read_f32_le(&mut self) -> Result<f32, Error>;
-  fn read_f64_le(&mut self) -> Result<f64, Error>;
+  fn read_u16(&mut self) -> Result<u16, Error> {
+    match self.endian() {
+      Endian::BigEndian => self.read_u16_be(),
+      Endian::LittleEndian => self.read_u16_le(),
+    }
+  }
+
+  fn read_u32(&mut self) -> Result<u32, Error> {
+    match self.endian() {
+      Endian::BigEndian => self.read_u32_be(),
+      Endian::LittleEndian => self.read_u32_le(),
+    }
+  }
+
+  fn read_u64(&mut self) -> Result<u64, Error> {
+    match self.endian() {
+      Endian::BigEndian => self.read_u64_be(),
+      Endian::LittleEndian => self.read_u64_le(),
+    }
+  }
+
+  fn read_u128(&mut self) -> Result<u128, Error> {
+    match self.endian() {
+      Endian::BigEndian => self.read_u128_be(),
+      Endian::LittleEndian => self.read_u128_le(),
+    }
+  }
+
+  fn read_i8(&mut self) -> Result<i8, Error> {
+    Ok(self.read_byte()? as i8)
+  }
+
+  fn read_i16(&mut self) -> Result<i16, Error> {
+    match self.endian() {
+      Endian::BigEndian => self.read_i16_be(),
+      Endian::LittleEndian => self.read_i16_le(),
+    }
+  }
+
+  fn read_i32(&mut self) -> Result<i32, Error> {
+    match self.endian() {
+      Endian::BigEndian => self.read_i32_be(),
+      Endian::LittleEndian => self.read_i32_le(),
+    }
+  }
+
+  fn read_i64(&mut self) -> Result<i64, Error> {
+    match self.endian() {
+      Endian::BigEndian => self.read_i64_be(),
+      Endian::LittleEndian => self.read_i64_le(),
+    }
+  }
+
+  fn read_i128(&mut self) -> Result<i128, Error> {
+    match self.endian() {
+      Endian::BigEndian => self.read_i128_be(),
+      Endian::LittleEndian => self.read_i128_le(),
+    }
+  }
+
+  fn read_f32(&mut self) -> Result<f32, Error> {
+    match self.endian() {
+      Endian::BigEndian => self.read_f32_be(),
+      Endian::LittleEndian => self.read_f32_le(),
+    }
+  }
+
+  fn read_f64(&mut self) -> Result<f64, Error> {
+    match self.endian() {
+      Endian::BigEndian => self.read_f64_be(),
+      Endian::LittleEndian => self.read_f64_le(),
+    }
+  }
+
+  fn read_u16_be(&mut self) -> Result<u16, Error> {
+    let mut array = [0; 2];
+    self.read_exact(&mut array)?;
+    Ok(u16::from_be_bytes(array))
+  }
+
+  fn read_u32_be(&mut self) -> Result<u32, Error> {
+    let mut array = [0; 4];
+    self.read_exact(&mut array)?;
+    Ok(u32::from_be_bytes(array))
+  }
+
+  fn read_u64_be(&mut self) -> Result<u64, Error> {
+    let mut array = [0; 8];
+    self.read_exact(&mut array)?;
+    Ok(u64::from_be_bytes(array))
+  }
+
+  fn read_u128_be(&mut self) -> Result<u128, Error> {
+    let mut array = [0; 16];
+    self.read_exact(&mut array)?;
+    Ok(u128::from_be_bytes(array))
+  }
+
+  fn read_i16_be(&mut self) -> Result<i16, Error> {
+    let mut array = [0; 2];
+    self.read_exact(&mut array)?;
+    Ok(i16::from_be_bytes(array))
+  }
+
+  fn read_i32_be(&mut self) -> Result<i32, Error> {
+    let mut array = [0; 4];
+    self.read_exact(&mut array)?;
+    Ok(i32::from_be_bytes(array))
+  }
+
+  fn read_i64_be(&mut self) -> Result<i64, Error> {
+    let mut array = [0; 8];
+    self.read_exact(&mut array)?;
+    Ok(i64::from_be_bytes(array))
+  }
+
+  fn read_i128_be(&mut self) -> Result<i128, Error> {
+    let mut array = [0; 16];
+    self.read_exact(&mut array)?;
+    Ok(i128::from_be_bytes(array))
+  }
+
+  fn read_f32_be(&mut self) -> Result<f32, Error> {
+    let mut array = [0; 4];
+    self.read_exact(&mut array)?;
+    Ok(f32::from_be_bytes(array))
+  }
+
+  fn read_f64_be(&mut self) -> Result<f64, Error> {
+    let mut array = [0; 8];
+    self.read_exact(&mut array)?;
+    Ok(f64::from_be_bytes(array))
+  }
+
+  fn read_u16_le(&mut self) -> Result<u16, Error> {
+    let mut array = [0; 2];
+    self.read_exact(&mut array)?;
+    Ok(u16::from_le_bytes(array))
+  }
+
+  fn read_u32_le(&mut self) -> Result<u32, Error> {
+    let mut array = [0; 4];
+    self.read_exact(&mut array)?;
+    Ok(u32::from_le_bytes(array))
+  }
+
+  fn read_u64_le(&mut self) -> Result<u64, Error> {
+    let mut array = [0; 8];
+    self.read_exact(&mut array)?;
+    Ok(u64::from_le_bytes(array))
+  }
+
+  fn read_u128_le(&mut self) -> Result<u128, Error> {
+    let mut array = [0; 16];
+    self.read_exact(&mut array)?;
+    Ok(u128::from_le_bytes(array))
+  }
+
+  fn read_i16_le(&mut self) -> Result<i16, Error> {
+    let mut array = [0; 2];
+    self.read_exact(&mut array)?;
+    Ok(i16::from_le_bytes(array))
+  }
+
+  fn read_i32_le(&mut self) -> Result<i32, Error> {
+    let mut array = [0; 4];
+    self.read_exact(&mut array)?;
+    Ok(i32::from_le_bytes(array))
+  }
+
+  fn read_i64_le(&mut self) -> Result<i64, Error> {
+    let mut array = [0; 8];
+    self.read_exact(&mut array)?;
+    Ok(i64::from_le_bytes(array))
+  }
+
+  fn read_i128_le(&mut self) -> Result<i128, Error> {
+    let mut array = [0; 16];
+    self.read_exact(&mut array)?;
+    Ok(i128::from_le_bytes(array))
+  }
+
+  fn read_f32_le(&mut self) -> Result<f32, Error> {
+    let mut array = [0; 4];
+    self.read_exact(&mut array)?;
+    Ok(f32::from_le_bytes(array))
+  }
+
+  fn read_f64_le(&mut self) -> Result<f64, Error> {
+    let mut array = [0; 8];
+    self.read_exact(&mut array)?;
+    Ok(f64::from_le_bytes(array))
+  }
+
+  /// read reads any FromBinary type with the reader's endian.
+  /// ```
+  /// use bin_rs::reader::*;
+  /// use std::io::Error;
+  ///
+  /// fn test() -> Result<(), Error> {
+  ///   let buffer = b"\x00\x01\x02\x03\x04\x05";
+  ///   let mut reader = BytesReader::new(buffer);
+  ///   let (a, b) = reader.read_with::<(u16, [u8; 2])>(bin_rs::Endian::BigEndian)?;
+  ///   assert_eq!(a, 1);
+  ///   assert_eq!(b, [2, 3]);
+  ///   Ok(())
+  /// }
+  /// ```
+  fn read<T: FromBinary>(&mut self) -> Result<T, Error> {
+    let endian = self.endian();
+    T::read_from(self, endian)
+  }
+
+  /// read_with reads any FromBinary type with `endian`.
+  fn read_with<T: FromBinary>(&mut self, endian: Endian) -> Result<T, Error> {
+    T::read_from(self, endian)
+  }
+
+  /// with_context runs `f`, and adds `label` and the offset where `f` began to its error.
+  /// Nested calls report the chain of labels.
+  /// ```
+  /// use bin_rs::error::BinError;
+  /// use bin_rs::reader::*;
+  ///
+  /// let mut reader = BytesReader::new(b"\x00\x02\x01");
+  /// let err = reader
+  ///   .with_context("reading IFD", |r| {
+  ///     let count = r.read_u16_be()?;
+  ///     for i in 0..count {
+  ///       r.with_context(format!("reading IFD entry {}", i), |r| r.read_u16_be())?;
+  ///     }
+  ///     Ok(())
+  ///   })
+  ///   .unwrap_err();
+  /// assert_eq!(
+  ///   err.to_string(),
+  ///   "reading IFD (at 0): reading IFD entry 0 (at 2): \
+  ///    unexpected eof at 2: requested 2 bytes, but 1 bytes available"
+  /// );
+  /// let err = BinError::from(err);
+  /// assert_eq!(err.contexts(), [("reading IFD", 0), ("reading IFD entry 0", 2)]);
+  /// assert!(matches!(err.root(), BinError::UnexpectedEof { .. }));
+  /// ```
+  fn with_context<T, L, F>(&mut self, label: L, f: F) -> Result<T, Error>
+  where
+    L: std::fmt::Display,
+    F: FnOnce(&mut Self) -> Result<T, Error>,
+  {
+    let offset = self.offset()?;
+    f(self).map_err(|err| {
+      BinError::from(err)
+        .context(label.to_string(), offset)
+        .into()
+    })
+  }
+
+  /// sub_reader returns a view of `len` bytes from the current offset. See `SubReader`.
+  fn sub_reader(&mut self, len: u64) -> Result<SubReader<'_, Self>, Error> {
+    SubReader::new(self, len)
+  }
+
+  /// window seeks to `offset`, and returns a view of `len` bytes from there.
+  fn window(&mut self, offset: u64, len: u64) -> Result<SubReader<'_, Self>, Error> {
+    self.seek(SeekFrom::Start(offset))?;
+    SubReader::new(self, len)
+  }
+
+  /// read_vec reads `count` elements of T with the reader's endian.
+  fn read_vec<T: FromBinary>(&mut self, count: usize) -> Result<Vec<T>, Error> {
+    let endian = self.endian();
+    let mut array = Vec::with_capacity(count.min(4096));
+    for _ in 0..count {
+      array.push(T::read_from(self, endian)?);
+    }
+    Ok(array)
+  }
+
+  /// read_uleb128 reads unsigned LEB128 (DWARF, WebAssembly).
+  /// Overlong encodings and values over u64 are errors.
+  fn read_uleb128(&mut self) -> Result<u64, Error> {
+    read_varint(self, Uleb128::default())
+  }
+
+  /// read_sleb128 reads signed LEB128 (DWARF, WebAssembly).
+  /// Overlong encodings and values over i64 are errors.
+  fn read_sleb128(&mut self) -> Result<i64, Error> {
+    read_varint(self, Sleb128::default())
+  }
+
+  /// read_zigzag_varint reads protobuf style sint64, a zigzag encoded uleb128.
+  fn read_zigzag_varint(&mut self) -> Result<i64, Error> {
+    Ok(decode_zigzag(self.read_uleb128()?))
+  }
+
+  /// read_vlq reads a big-endian variable-length quantity (MIDI).
+  /// Leading 0x80 bytes and values over u64 are errors.
+  fn read_vlq(&mut self) -> Result<u64, Error> {
+    read_varint(self, Vlq::default())
+  }
 
   /// read_ascii_string for C like ascii string.This function finishes find end marker 0x00.
   /// ```
@@ -76,7 +359,6 @@ pub trait BinaryReader {
   ///   return Ok(r)
   /// }
   /// ```
-
   fn read_ascii_string(&mut self, size: usize) -> Result<String, Error> {
     let mut array: Vec<u8> = vec![0; size];
     self.read_exact(&mut array)?;
@@ -93,8 +375,9 @@ pub trait BinaryReader {
     match res {
       Ok(strings) => Ok(strings),
       _ => {
-        let err = "This string can not read";
-        Err(Error::new(ErrorKind::Other, err))
+        let offset = start_offset(self, size)?;
+        let encoding = "US-ASCII";
+        Err(BinError::InvalidString { offset, encoding }.into())
       }
     }
   }
@@ -105,15 +388,16 @@ pub trait BinaryReader {
   fn read_utf16_string(&mut self, size: usize) -> Result<String, Error> {
     let size = size / 2;
     let mut array: Vec<u16> = vec![0; size];
-    for i in 0..size {
-      array[i] = self.read_u16()?;
+    for a in array.iter_mut() {
+      *a = self.read_u16()?;
     }
     let res = String::from_utf16(&array);
     match res {
       Ok(strings) => Ok(strings),
       _ => {
-        let err = "This string can not read";
-        Err(Error::new(ErrorKind::Other, err))
+        let offset = start_offset(self, size * 2)?;
+        let encoding = "UTF-16";
+        Err(BinError::InvalidString { offset, encoding }.into())
       }
     }
   }
@@ -136,21 +420,154 @@ pub trait BinaryReader {
 
   fn read_utf8_string(&mut self, size: usize) -> Result<String, Error> {
     let mut array: Vec<u8> = vec![0; size];
-    for i in 0..size {
-      array[i] = self.read_u8()?;
+    for a in array.iter_mut() {
+      *a = self.read_u8()?;
     }
     let res = String::from_utf8(array);
     match res {
       Ok(strings) => Ok(strings),
       _ => {
-        let err = "This string can not read";
-        Err(Error::new(ErrorKind::Other, err))
+        let offset = start_offset(self, size)?;
+        let encoding = "UTF-8";
+        Err(BinError::InvalidString { offset, encoding }.into())
+      }
+    }
+  }
+
+  /// read_fixed_string reads a string in a `width` bytes field padded with NUL or space.
+  /// `width` bytes are always consumed. UTF-16 needs an even `width`.
+  /// ```
+  /// use bin_rs::reader::*;
+  /// use std::io::Error;
+  ///
+  /// fn test() -> Result<(), Error> {
+  ///   let buffer = b"README  TXT\x20hello.txt\x00\x00\x00";
+  ///   let mut reader = BytesReader::new(buffer);
+  ///   assert_eq!(reader.read_fixed_string(8, CodeType::Ascii, Trim::Space)?, "README");
+  ///   assert_eq!(reader.read_fixed_string(4, CodeType::Ascii, Trim::None)?, "TXT ");
+  ///   assert_eq!(reader.read_fixed_string(12, CodeType::Utf8, Trim::Nul)?, "hello.txt");
+  ///   Ok(())
+  /// }
+  /// ```
+  fn read_fixed_string(
+    &mut self,
+    width: usize,
+    code: CodeType,
+    trim: Trim,
+  ) -> Result<String, Error> {
+    check_supported(code)?;
+    check_width(width, code)?;
+    let array = self.read_bytes_as_vec(width)?;
+    crate::codec::decode_fixed(&array, code, trim).map_err(|e| malformed(self, e))
+  }
+
+  /// read_pascal_bytes reads bytes after a length prefix in the reader's endian.
+  fn read_pascal_bytes(&mut self, prefix: LengthPrefix) -> Result<Vec<u8>, Error> {
+    let len = match prefix {
+      LengthPrefix::U8 => self.read_u8()? as usize,
+      LengthPrefix::U16 => self.read_u16()? as usize,
+      LengthPrefix::U32 => self.read_u32()? as usize,
+    };
+    self.read_bytes_as_vec(len)
+  }
+
+  /// read_pascal_string reads utf8 string after a length prefix in the reader's endian.
+  /// ```
+  /// use bin_rs::reader::*;
+  /// use std::io::Error;
+  ///
+  /// fn test() -> Result<(), Error> {
+  ///   let mut reader = BytesReader::new(b"\x05Hello\x00\x06World!");
+  ///   assert_eq!(reader.read_pascal_string(LengthPrefix::U8)?, "Hello");
+  ///   reader.set_endian(bin_rs::Endian::BigEndian);
+  ///   assert_eq!(reader.read_pascal_string(LengthPrefix::U16)?, "World!");
+  ///   Ok(())
+  /// }
+  /// ```
+  fn read_pascal_string(&mut self, prefix: LengthPrefix) -> Result<String, Error> {
+    let array = self.read_pascal_bytes(prefix)?;
+    let len = array.len();
+    crate::codec::decode_utf8(array, len).map_err(|e| malformed(self, e))
+  }
+
+  /// read_until reads bytes until `delimiter` or the end of data.
+  /// `delimiter` is included, like `BufRead::read_until`.
+  fn read_until(&mut self, delimiter: u8) -> Result<Vec<u8>, Error> {
+    let mut array = Vec::new();
+    while let Some(b) = end_of_data(self.read_byte())? {
+      array.push(b);
+      if b == delimiter {
+        break;
       }
     }
+    Ok(array)
+  }
+
+  /// read_cstring reads utf8 string until NUL. NUL is consumed but not returned.
+  /// It is an error if data ends before NUL.
+  fn read_cstring(&mut self) -> Result<String, Error> {
+    let array = self.read_until(0)?;
+    crate::codec::decode_cstring(array).map_err(|e| malformed(self, e))
+  }
+
+  /// read_line_bytes reads a line without "\n" or "\r\n".
+  /// It returns None at the end of data.
+  fn read_line_bytes(&mut self) -> Result<Option<Vec<u8>>, Error> {
+    Ok(crate::codec::line_bytes(self.read_until(b'\n')?))
+  }
+
+  /// read_line reads a utf8 line without "\n" or "\r\n".
+  /// It returns None at the end of data.
+  /// ```
+  /// use bin_rs::reader::*;
+  /// use std::io::Error;
+  ///
+  /// fn test() -> Result<(), Error> {
+  ///   let mut reader = BytesReader::new(b"P1\r\n# comment\n1 0");
+  ///   let mut lines = Vec::new();
+  ///   while let Some(line) = reader.read_line()? {
+  ///     lines.push(line);
+  ///   }
+  ///   assert_eq!(lines, ["P1", "# comment", "1 0"]);
+  ///   Ok(())
+  /// }
+  /// ```
+  fn read_line(&mut self) -> Result<Option<String>, Error> {
+    let array = self.read_until(b'\n')?;
+    crate::codec::decode_line(array).map_err(|e| malformed(self, e))
+  }
+
+  /// read_local_string reads `size` bytes string in `code`, feature="codec".
+  /// It ends at NUL, and malformed bytes are errors. See `read_local_string_with`.
+  /// ```
+  /// use bin_rs::reader::*;
+  /// use std::io::Error;
+  ///
+  /// fn test() -> Result<(), Error> {
+  ///   let buffer = b"\x93\xfa\x96\x7b\x8c\xea\x00\x00";
+  ///   let mut reader = BytesReader::new(buffer);
+  ///   let r = reader.read_local_string(buffer.len(), CodeType::ShiftJis)?;
+  ///   assert_eq!(r, "日本語");
+  ///   Ok(())
+  /// }
+  /// ```
+  #[cfg(feature = "codec")]
+  fn read_local_string(&mut self, size: usize, code: CodeType) -> Result<String, Error> {
+    self.read_local_string_with(size, code, DecodeOptions::default())
   }
 
+  /// read_local_string_with reads `size` bytes string in `code` with `options`, feature="codec".
+  /// `size` bytes are always consumed. A leading BOM of UTF-8 or UTF-16 is removed.
   #[cfg(feature = "codec")]
-  fn read_local_string(&mut self, size: usize, code: CodeType) -> Result<String, Error>;
+  fn read_local_string_with(
+    &mut self,
+    size: usize,
+    code: CodeType,
+    options: DecodeOptions,
+  ) -> Result<String, Error> {
+    let array = self.read_bytes_as_vec(size)?;
+    crate::codec::decode_local(&array, code, options).map_err(|e| malformed(self, e))
+  }
 
   /// skip size byte
   fn skip_ptr(&mut self, size: usize) -> Result<usize, Error>;
@@ -158,3 +575,61 @@ pub trait BinaryReader {
   fn offset(&mut self) -> Result<u64, Error>;
   fn seek(&mut self, seek: SeekFrom) -> Result<u64, Error>;
 }
+
+// start_offset returns the offset `len` bytes before the current offset,
+// where a failed value starts.
+fn start_offset<R: BinaryReader + ?Sized>(reader: &mut R, len: usize) -> Result<u64, Error> {
+  Ok(reader.offset()?.saturating_sub(len as u64))
+}
+
+pub(crate) fn check_supported(code: CodeType) -> Result<(), Error> {
+  if code.is_supported() {
+    Ok(())
+  } else {
+    let s = format!("{} needs feature=\"codec\"", code.name());
+    Err(BinError::InvalidInput(s).into())
+  }
+}
+
+// check_width rejects a fixed `width` which is not a multiple of the code unit of `code`.
+pub(crate) fn check_width(width: usize, code: CodeType) -> Result<(), Error> {
+  match code {
+    CodeType::Utf16Be | CodeType::Utf16le if width % 2 != 0 => {
+      let s = format!("{} needs an even width, but {}", code.name(), width);
+      Err(BinError::InvalidInput(s).into())
+    }
+    _ => Ok(()),
+  }
+}
+
+// malformed returns the error of `e` at the offset where the failed value starts.
+fn malformed<R: BinaryReader + ?Sized>(reader: &mut R, e: Malformed) -> Error {
+  match start_offset(reader, e.len()) {
+    Ok(offset) => e.error(offset),
+    Err(err) => err,
+  }
+}
+
+// read_varint reads bytes until `decoder` returns the value.
+fn read_varint<R: BinaryReader + ?Sized, V: Varint>(
+  reader: &mut R,
+  mut decoder: V,
+) -> Result<V::Value, Error> {
+  loop {
+    let b = reader.read_byte()?;
+    match decoder.push(b) {
+      Ok(Some(value)) => return Ok(value),
+      Ok(None) => {}
+      Err(e) => return Err(malformed(reader, e)),
+    }
+  }
+}
+
+// end_of_data returns None for UnexpectedEof of a byte read, where read_until stops.
+pub(crate) fn end_of_data(result: Result<u8, Error>) -> Result<Option<u8>, Error> {
+  match result {
+    Ok(b) => Ok(Some(b)),
+    Err(e) if e.kind() == ErrorKind::UnexpectedEof => Ok(None),
+    Err(e) => Err(e),
+  }
+}
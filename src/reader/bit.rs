@@ -0,0 +1,141 @@
+use crate::error::BinError;
+use crate::BitOrder;
+use std::io::{Error, SeekFrom};
+
+use super::BinaryReader;
+
+/// BitReader reads sub-byte fields from any BinaryReader.
+/// ```
+/// use bin_rs::reader::*;
+/// use bin_rs::BitOrder;
+/// use std::io::Error;
+///
+/// fn test() -> Result<(), Error> {
+///   let buffer = [0b1011_0011, 0b0101_0101];
+///   let mut reader = BitReader::new(BytesReader::new(&buffer), BitOrder::MsbFirst);
+///   assert_eq!(reader.read_bits(3)?, 0b101);
+///   assert_eq!(reader.read_bit()?, true);
+///   assert_eq!(reader.read_bits(8)?, 0b0011_0101);
+///   Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct BitReader<R> {
+  reader: R,
+  order: BitOrder,
+  cache: u128,
+  cache_bits: u32,
+  bit_offset: u64,
+}
+
+impl<R: BinaryReader> BitReader<R> {
+  pub fn new(reader: R, order: BitOrder) -> Self {
+    Self {
+      reader,
+      order,
+      cache: 0,
+      cache_bits: 0,
+      bit_offset: 0,
+    }
+  }
+
+  pub fn order(&self) -> BitOrder {
+    self.order
+  }
+
+  /// bit_offset returns the number of bits read since this BitReader was created.
+  pub fn bit_offset(&self) -> u64 {
+    self.bit_offset
+  }
+
+  pub fn is_aligned(&self) -> bool {
+    self.bit_offset % 8 == 0
+  }
+
+  pub fn get_ref(&self) -> &R {
+    &self.reader
+  }
+
+  /// into_inner returns the inner reader.
+  /// Whole bytes fetched by peek_bits are given back by seeking,
+  /// a partially read byte is treated as read.
+  pub fn into_inner(mut self) -> Result<R, Error> {
+    let unread = (self.cache_bits / 8) as i64;
+    if unread > 0 {
+      self.reader.seek(SeekFrom::Current(-unread))?;
+    }
+    Ok(self.reader)
+  }
+
+  /// peek_bits reads up to 64 bits without moving the bit offset.
+  pub fn peek_bits(&mut self, n: u32) -> Result<u64, Error> {
+    Self::check_bits(n)?;
+    while self.cache_bits < n {
+      let b = self.reader.read_byte()? as u128;
+      match self.order {
+        BitOrder::MsbFirst => self.cache = (self.cache << 8) | b,
+        BitOrder::LsbFirst => self.cache |= b << self.cache_bits,
+      }
+      self.cache_bits += 8;
+    }
+    let bits = match self.order {
+      BitOrder::MsbFirst => (self.cache >> (self.cache_bits - n)) & mask(n),
+      BitOrder::LsbFirst => self.cache & mask(n),
+    };
+    Ok(bits as u64)
+  }
+
+  /// read_bits reads up to 64 bits as an unsigned number.
+  pub fn read_bits(&mut self, n: u32) -> Result<u64, Error> {
+    let bits = self.peek_bits(n)?;
+    self.consume(n);
+    Ok(bits)
+  }
+
+  pub fn read_bit(&mut self) -> Result<bool, Error> {
+    Ok(self.read_bits(1)? == 1)
+  }
+
+  /// read_signed_bits reads up to 64 bits as a two's complement number.
+  pub fn read_signed_bits(&mut self, n: u32) -> Result<i64, Error> {
+    let bits = self.read_bits(n)?;
+    if n == 0 {
+      return Ok(0);
+    }
+    let shift = 64 - n;
+    Ok(((bits << shift) as i64) >> shift)
+  }
+
+  /// align_to_byte skips the rest bits of the current byte.
+  pub fn align_to_byte(&mut self) {
+    let rest = ((8 - self.bit_offset % 8) % 8) as u32;
+    self.consume(rest);
+  }
+
+  fn consume(&mut self, n: u32) {
+    match self.order {
+      BitOrder::MsbFirst => {
+        self.cache_bits -= n;
+        self.cache &= mask(self.cache_bits);
+      }
+      BitOrder::LsbFirst => {
+        self.cache >>= n;
+        self.cache_bits -= n;
+      }
+    }
+    self.bit_offset += n as u64;
+  }
+
+  fn check_bits(n: u32) -> Result<(), Error> {
+    if n > 64 {
+      let s = format!("read bits must be 64 bits or less, but {}", n);
+      Err(BinError::InvalidInput(s).into())
+    } else {
+      Ok(())
+    }
+  }
+}
+
+fn mask(n: u32) -> u128 {
+  (1 << n) - 1
+}
@@ -1,15 +1,53 @@
+use crate::error::BinError;
 use crate::Endian;
-use std::io::{Error, ErrorKind, SeekFrom};
+use std::io::{BufRead, Error, Read, Seek, SeekFrom};
 
 use super::BinaryReader;
 
 /// BytesReader from creating Slice `&[u8]` or `Vec<u8>`,
 /// no use Read trait
+///
+/// 0.0.11 BytesReader also implements `std::io::Read`, `BufRead` and `Seek` on the same offset,
+/// so it can be passed to decompressors or `std::io::copy`.
+/// `read`, `read_exact`, `seek`, `read_until` and `read_line` have the same names as BinaryReader's,
+/// so call them as `Read::read_exact(&mut reader, ..)` if both traits are in scope.
+/// ```
+/// use bin_rs::reader::*;
+/// use std::io::Error;
+///
+/// fn test() -> Result<(), Error> {
+///   let mut reader = BytesReader::new(b"\x00\x05hello world");
+///   let len = reader.read_u16_be()? as usize;
+///   assert_eq!(reader.read_utf8_string(len)?, "hello");
+///   let mut rest = Vec::new();
+///   std::io::copy(&mut reader, &mut rest)?;
+///   assert_eq!(rest, b" world");
+///   Ok(())
+/// }
+/// ```
 #[derive(Debug, Clone)]
 pub struct BytesReader {
   buffer: Vec<u8>,
   ptr: usize,
   endian: Endian,
+  incremental: bool,
+  // bytes dropped by compact
+  base: u64,
+}
+
+/// Checkpoint is a position of `BytesReader` to roll back to.
+///
+/// It counts from the first appended byte, so it is still valid after `compact`
+/// unless the position itself is dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint(u64);
+
+impl Checkpoint {
+  /// offset returns the position in all data appended to the reader.
+  /// It is the offset of the reader until `compact` is called.
+  pub fn offset(&self) -> u64 {
+    self.0
+  }
 }
 
 impl BytesReader {
@@ -18,6 +56,8 @@ impl BytesReader {
       buffer: buffer.to_vec(),
       ptr: 0,
       endian: crate::system_endian(),
+      incremental: false,
+      base: 0,
     }
   }
 
@@ -26,15 +66,114 @@ impl BytesReader {
     Self::from(buffer)
   }
 
-  fn check_bound(&mut self, size: usize) -> Result<(), Error> {
-    if self.ptr + size > self.buffer.len() {
-      let s = format!(
-        "ountbound call ptr {} + {} but buffer length {}",
-        self.ptr,
-        size,
-        &self.buffer.len()
+  /// set_incremental switches incremental mode for data arriving in pieces.
+  ///
+  /// In incremental mode, a read beyond the buffered data returns `BinError::Incomplete`
+  /// (`ErrorKind::WouldBlock`) instead of `UnexpectedEof`, and `read_until` waits for the delimiter.
+  /// Take a `checkpoint` before a record, and when it is incomplete,
+  /// `rollback`, `append` more bytes and read it again.
+  /// `with_context` wraps the error, so match `BinError::root` like below.
+  /// `seek` can move to the end of the buffered data.
+  /// Turn it off when no more data comes, then the rest is read as usual.
+  /// ```
+  /// use bin_rs::error::BinError;
+  /// use bin_rs::reader::*;
+  /// use std::io::Error;
+  ///
+  /// fn next_record(reader: &mut BytesReader) -> Result<Option<String>, Error> {
+  ///   let checkpoint = reader.checkpoint();
+  ///   let result = reader.read_u16_be().and_then(|len| reader.read_utf8_string(len as usize));
+  ///   match result {
+  ///     Ok(text) => Ok(Some(text)),
+  ///     Err(e) => {
+  ///       // match the root, because `with_context` wraps errors
+  ///       let err = BinError::from(e);
+  ///       match err.root() {
+  ///         BinError::Incomplete { .. } => {
+  ///           reader.rollback(checkpoint)?;
+  ///           Ok(None)
+  ///         }
+  ///         _ => Err(err.into()),
+  ///       }
+  ///     }
+  ///   }
+  /// }
+  ///
+  /// fn test() -> Result<(), Error> {
+  ///   let mut reader = BytesReader::new(b"\x00\x05hel");
+  ///   reader.set_incremental(true);
+  ///   assert_eq!(next_record(&mut reader)?, None);
+  ///   reader.append(b"lo\x00");
+  ///   assert_eq!(next_record(&mut reader)?, Some("hello".to_string()));
+  ///   assert_eq!(next_record(&mut reader)?, None);
+  ///   reader.compact();
+  ///   reader.append(b"\x02hi");
+  ///   assert_eq!(next_record(&mut reader)?, Some("hi".to_string()));
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn set_incremental(&mut self, incremental: bool) {
+    self.incremental = incremental;
+  }
+
+  pub fn is_incremental(&self) -> bool {
+    self.incremental
+  }
+
+  /// append adds bytes to the end of the buffer.
+  pub fn append(&mut self, data: &[u8]) {
+    self.buffer.extend_from_slice(data);
+  }
+
+  /// checkpoint returns the current position.
+  pub fn checkpoint(&self) -> Checkpoint {
+    Checkpoint(self.base + self.ptr as u64)
+  }
+
+  /// rollback moves back to `checkpoint`.
+  /// It is an error if `compact` dropped the position.
+  pub fn rollback(&mut self, checkpoint: Checkpoint) -> Result<(), Error> {
+    let position = checkpoint.0 as i128 - self.base as i128;
+    if position < 0 || position > self.buffer.len() as i128 {
+      return Err(
+        BinError::SeekOutOfRange {
+          position,
+          length: self.buffer.len() as u64,
+        }
+        .into(),
       );
-      Err(Error::new(ErrorKind::Other, s))
+    }
+    self.ptr = position as usize;
+    Ok(())
+  }
+
+  /// compact drops the bytes already read, and the offset restarts from 0.
+  /// Checkpoints after the current position are still valid.
+  pub fn compact(&mut self) {
+    self.buffer.drain(..self.ptr);
+    self.base += self.ptr as u64;
+    self.ptr = 0;
+  }
+
+  fn check_bound(&mut self, size: usize) -> Result<(), Error> {
+    let available = self.buffer.len().saturating_sub(self.ptr);
+    if size > available && self.incremental {
+      Err(
+        BinError::Incomplete {
+          offset: self.ptr as u64,
+          needed: size - available,
+        }
+        .into(),
+      )
+    } else if size > available {
+      Err(
+        BinError::UnexpectedEof {
+          offset: self.ptr as u64,
+          requested: size,
+          available,
+        }
+        .into(),
+      )
     } else {
       Ok(())
     }
@@ -47,6 +186,8 @@ impl From<Vec<u8>> for BytesReader {
       buffer,
       ptr: 0,
       endian: crate::system_endian(),
+      incremental: false,
+      base: 0,
     }
   }
 }
@@ -57,6 +198,8 @@ impl From<&[u8]> for BytesReader {
       buffer: buffer.to_vec(),
       ptr: 0,
       endian: crate::system_endian(),
+      incremental: false,
+      base: 0,
     }
   }
 }
@@ -67,6 +210,8 @@ impl From<&Vec<u8>> for BytesReader {
       buffer: buffer.to_vec(),
       ptr: 0,
       endian: crate::system_endian(),
+      incremental: false,
+      base: 0,
     }
   }
 }
@@ -79,6 +224,8 @@ impl From<bytes::Bytes> for BytesReader {
       buffer,
       ptr: 0,
       endian: crate::system_endian(),
+      incremental: false,
+      base: 0,
     }
   }
 }
@@ -114,19 +261,14 @@ impl BinaryReader for BytesReader {
   fn read_exact(&mut self, array: &mut [u8]) -> Result<(), Error> {
     let len = array.len();
     self.check_bound(len)?;
-    for i in 0..len {
-      array[i] = self.buffer[self.ptr + i];
-    }
+    array.copy_from_slice(&self.buffer[self.ptr..self.ptr + len]);
     self.ptr += len;
     Ok(())
   }
 
   fn read_bytes_as_vec(&mut self, len: usize) -> Result<Vec<u8>, Error> {
     self.check_bound(len)?;
-    let mut c: Vec<u8> = Vec::new();
-    for i in 0..len {
-      c.push(self.buffer[self.ptr + i]);
-    }
+    let c = self.buffer[self.ptr..self.ptr + len].to_vec();
     self.ptr += len;
     Ok(c)
   }
@@ -150,11 +292,7 @@ impl BinaryReader for BytesReader {
   ///
   fn read_bytes_no_move(&mut self, len: usize) -> Result<Vec<u8>, Error> {
     self.check_bound(len)?;
-    let mut c: Vec<u8> = Vec::new();
-    for i in 0..len {
-      c.push(self.buffer[self.ptr + i]);
-    }
-    Ok(c)
+    Ok(self.buffer[self.ptr..self.ptr + len].to_vec())
   }
 
   fn read_u16(&mut self) -> Result<u16, Error> {
@@ -552,11 +690,24 @@ impl BinaryReader for BytesReader {
     Ok(f64::from_le_bytes(array))
   }
 
-  #[cfg(feature = "codec")]
-  fn read_local_string(&mut self, size: usize, code: CodeType) -> Result<String, Error> {
-    self.check_bound(size)?;
-    self.ptr += size;
-    Err(Error::new(ErrorKind::Other, "No impl"));
+  fn read_until(&mut self, delimiter: u8) -> Result<Vec<u8>, Error> {
+    let rest = &self.buffer[self.ptr..];
+    let len = match rest.iter().position(|b| *b == delimiter) {
+      Some(i) => i + 1,
+      None if self.incremental => {
+        return Err(
+          BinError::Incomplete {
+            offset: self.ptr as u64,
+            needed: 1,
+          }
+          .into(),
+        )
+      }
+      None => rest.len(),
+    };
+    let array = rest[..len].to_vec();
+    self.ptr += len;
+    Ok(array)
   }
 
   /// skip_ptr skips offset size bytes
@@ -567,51 +718,75 @@ impl BinaryReader for BytesReader {
   }
 
   fn seek(&mut self, seek: SeekFrom) -> std::result::Result<u64, Error> {
-    match seek {
-      SeekFrom::Start(pos) => {
-        if pos >= usize::MAX as u64 {
-          let s = format!("BytesReader max offset is usize length but set {}", pos);
-          return Err(Error::new(ErrorKind::Other, s));
-        } else if pos >= self.buffer.len() as u64 {
-          let s = format!(
-            "set offset {},but buffer length is{}",
-            pos,
-            self.buffer.len()
-          );
-          return Err(Error::new(ErrorKind::Other, s));
+    let pos = match seek {
+      SeekFrom::Start(pos) => pos as i128,
+      SeekFrom::End(pos) => self.buffer.len() as i128 + pos as i128,
+      SeekFrom::Current(pos) => self.ptr as i128 + pos as i128,
+    };
+    let end = self.buffer.len() as i128;
+    if self.incremental && pos > end {
+      return Err(
+        BinError::Incomplete {
+          offset: self.ptr as u64,
+          needed: (pos - end) as usize,
         }
-        self.ptr = pos as usize;
-        Ok(self.ptr as u64)
-      }
-      SeekFrom::End(pos_) => {
-        let pos = self.buffer.len() as i64 + pos_;
-        if pos < 0 || pos >= (self.buffer.len() as i64) {
-          let s = format!(
-            "set offset {},but buffer length is {}",
-            pos,
-            self.buffer.len()
-          );
-          return Err(Error::new(ErrorKind::Other, s));
+        .into(),
+      );
+    }
+    // the end of data is a valid position, like an empty read at the end
+    if pos < 0 || pos > end {
+      return Err(
+        BinError::SeekOutOfRange {
+          position: pos,
+          length: self.buffer.len() as u64,
         }
-        self.ptr = pos as usize;
-        Ok(self.ptr as u64)
-      }
-      SeekFrom::Current(pos) => {
-        let ptr = (self.ptr as i64) + pos;
-        if ptr >= usize::MAX as i64 {
-          let s = format!("BytesReader max offset is usize length but set {}", ptr);
-          return Err(Error::new(ErrorKind::Other, s));
-        } else if self.buffer.len() as i64 <= ptr || ptr < 0 {
-          let s = format!(
-            "set offset {},but buffer length is{}",
-            ptr,
-            self.buffer.len()
-          );
-          return Err(Error::new(ErrorKind::Other, s));
+        .into(),
+      );
+    }
+    self.ptr = pos as usize;
+    Ok(self.ptr as u64)
+  }
+}
+
+impl Read for BytesReader {
+  fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+    let rest = &self.buffer[self.ptr..];
+    let len = rest.len().min(buf.len());
+    buf[..len].copy_from_slice(&rest[..len]);
+    self.ptr += len;
+    Ok(len)
+  }
+}
+
+impl BufRead for BytesReader {
+  fn fill_buf(&mut self) -> Result<&[u8], Error> {
+    Ok(&self.buffer[self.ptr..])
+  }
+
+  fn consume(&mut self, amt: usize) {
+    self.ptr = (self.ptr + amt).min(self.buffer.len());
+  }
+}
+
+/// Like `BinaryReader::seek`, the end of data is a valid position, but beyond it is an error
+/// even in incremental mode.
+impl Seek for BytesReader {
+  fn seek(&mut self, seek: SeekFrom) -> Result<u64, Error> {
+    let pos = match seek {
+      SeekFrom::Start(pos) => pos as i128,
+      SeekFrom::End(pos) => self.buffer.len() as i128 + pos as i128,
+      SeekFrom::Current(pos) => self.ptr as i128 + pos as i128,
+    };
+    if pos < 0 || pos > self.buffer.len() as i128 {
+      return Err(
+        BinError::SeekOutOfRange {
+          position: pos,
+          length: self.buffer.len() as u64,
         }
-        self.ptr = ptr as usize;
-        Ok(self.ptr as u64)
-      }
+        .into(),
+      );
     }
+    self.ptr = pos as usize;
+    Ok(self.ptr as u64)
   }
 }
@@ -0,0 +1,111 @@
+use crate::error::BinError;
+use crate::Endian;
+use std::io::Error;
+
+use super::BinaryReader;
+
+/// FromBinary is a type which can be read by `BinaryReader::read` or `read_with`.
+///
+/// It is implemented for numbers, `bool`, arrays `[T; N]`, tuples
+/// and `Option<T>` tagged by a leading byte (0 = None, 1 = Some).
+/// ```
+/// use bin_rs::reader::*;
+/// use std::io::Error;
+///
+/// fn read_points<R: BinaryReader>(reader: &mut R) -> Result<Vec<(i16, i16)>, Error> {
+///   let count = reader.read::<u8>()? as usize;
+///   reader.read_vec::<(i16, i16)>(count)
+/// }
+/// ```
+pub trait FromBinary: Sized {
+  fn read_from<R: BinaryReader + ?Sized>(reader: &mut R, endian: Endian) -> Result<Self, Error>;
+}
+
+macro_rules! impl_from_binary {
+  ($t:ty, $be:ident, $le:ident) => {
+    impl FromBinary for $t {
+      fn read_from<R: BinaryReader + ?Sized>(
+        reader: &mut R,
+        endian: Endian,
+      ) -> Result<Self, Error> {
+        match endian {
+          Endian::BigEndian => reader.$be(),
+          Endian::LittleEndian => reader.$le(),
+        }
+      }
+    }
+  };
+}
+
+impl_from_binary!(u8, read_u8, read_u8);
+impl_from_binary!(i8, read_i8, read_i8);
+impl_from_binary!(u16, read_u16_be, read_u16_le);
+impl_from_binary!(u32, read_u32_be, read_u32_le);
+impl_from_binary!(u64, read_u64_be, read_u64_le);
+impl_from_binary!(u128, read_u128_be, read_u128_le);
+impl_from_binary!(i16, read_i16_be, read_i16_le);
+impl_from_binary!(i32, read_i32_be, read_i32_le);
+impl_from_binary!(i64, read_i64_be, read_i64_le);
+impl_from_binary!(i128, read_i128_be, read_i128_le);
+impl_from_binary!(f32, read_f32_be, read_f32_le);
+impl_from_binary!(f64, read_f64_be, read_f64_le);
+
+impl FromBinary for bool {
+  fn read_from<R: BinaryReader + ?Sized>(reader: &mut R, _: Endian) -> Result<Self, Error> {
+    match reader.read_u8()? {
+      0 => Ok(false),
+      1 => Ok(true),
+      b => {
+        let offset = reader.offset()? - 1;
+        let message = format!("bool must be 0 or 1, but {}", b);
+        Err(BinError::InvalidData { offset, message }.into())
+      }
+    }
+  }
+}
+
+impl<T: FromBinary, const N: usize> FromBinary for [T; N] {
+  fn read_from<R: BinaryReader + ?Sized>(reader: &mut R, endian: Endian) -> Result<Self, Error> {
+    let mut array = Vec::with_capacity(N);
+    for _ in 0..N {
+      array.push(T::read_from(reader, endian)?);
+    }
+    match array.try_into() {
+      Ok(array) => Ok(array),
+      Err(_) => unreachable!(),
+    }
+  }
+}
+
+impl<T: FromBinary> FromBinary for Option<T> {
+  fn read_from<R: BinaryReader + ?Sized>(reader: &mut R, endian: Endian) -> Result<Self, Error> {
+    match reader.read_u8()? {
+      0 => Ok(None),
+      1 => Ok(Some(T::read_from(reader, endian)?)),
+      b => {
+        let offset = reader.offset()? - 1;
+        let message = format!("Option tag must be 0 or 1, but {}", b);
+        Err(BinError::InvalidData { offset, message }.into())
+      }
+    }
+  }
+}
+
+macro_rules! impl_from_binary_tuple {
+  ($($t:ident),+) => {
+    impl<$($t: FromBinary),+> FromBinary for ($($t,)+) {
+      fn read_from<R: BinaryReader + ?Sized>(reader: &mut R, endian: Endian) -> Result<Self, Error> {
+        Ok(($($t::read_from(reader, endian)?,)+))
+      }
+    }
+  };
+}
+
+impl_from_binary_tuple!(A);
+impl_from_binary_tuple!(A, B);
+impl_from_binary_tuple!(A, B, C);
+impl_from_binary_tuple!(A, B, C, D);
+impl_from_binary_tuple!(A, B, C, D, E);
+impl_from_binary_tuple!(A, B, C, D, E, F);
+impl_from_binary_tuple!(A, B, C, D, E, F, G);
+impl_from_binary_tuple!(A, B, C, D, E, F, G, H);
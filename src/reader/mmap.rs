@@ -0,0 +1,177 @@
+use crate::error::BinError;
+use crate::Endian;
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::{Error, SeekFrom};
+use std::path::PathBuf;
+
+use super::{BinaryReader, SliceReader};
+
+/// MmapReader reads a memory-mapped file, use feature="mmap"
+///
+/// Reads do not call the system. `read_slice` and `read_str` borrow from the map
+/// at the current offset, and `slice_reader` gives zero-copy access to the whole file.
+/// The file must not be modified by other processes while it is mapped.
+#[derive(Debug)]
+pub struct MmapReader {
+  mmap: Mmap,
+  ptr: usize,
+  endian: Endian,
+}
+
+impl MmapReader {
+  pub fn new(mmap: Mmap) -> Self {
+    Self {
+      mmap,
+      ptr: 0,
+      endian: crate::system_endian(),
+    }
+  }
+
+  pub fn from_file(filename: PathBuf) -> Result<Self, Error> {
+    let file = File::open(filename)?;
+    Self::try_from(file)
+  }
+
+  pub fn len(&self) -> usize {
+    self.mmap.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.mmap.is_empty()
+  }
+
+  pub fn as_slice(&self) -> &[u8] {
+    &self.mmap
+  }
+
+  /// slice_reader returns a SliceReader over the whole file with the same endian.
+  /// It is independent of this reader's offset.
+  pub fn slice_reader(&self) -> SliceReader<'_> {
+    let mut reader = SliceReader::new(&self.mmap);
+    reader.set_endian(self.endian);
+    reader
+  }
+
+  /// read_slice returns `len` bytes borrowed from the map and moves offset.
+  pub fn read_slice(&mut self, len: usize) -> Result<&[u8], Error> {
+    self.check_bound(len)?;
+    let slice = &self.mmap[self.ptr..self.ptr + len];
+    self.ptr += len;
+    Ok(slice)
+  }
+
+  /// read_str returns utf8 string borrowed from the map. "size" refers to the number of bytes.
+  pub fn read_str(&mut self, size: usize) -> Result<&str, Error> {
+    self.check_bound(size)?;
+    match std::str::from_utf8(&self.mmap[self.ptr..self.ptr + size]) {
+      Ok(s) => {
+        self.ptr += size;
+        Ok(s)
+      }
+      _ => Err(
+        BinError::InvalidString {
+          offset: self.ptr as u64,
+          encoding: "UTF-8",
+        }
+        .into(),
+      ),
+    }
+  }
+
+  fn check_bound(&self, size: usize) -> Result<(), Error> {
+    if size > self.mmap.len() - self.ptr {
+      Err(
+        BinError::UnexpectedEof {
+          offset: self.ptr as u64,
+          requested: size,
+          available: self.mmap.len() - self.ptr,
+        }
+        .into(),
+      )
+    } else {
+      Ok(())
+    }
+  }
+
+  fn set_ptr(&mut self, pos: i128) -> Result<u64, Error> {
+    if pos < 0 || pos > self.mmap.len() as i128 {
+      return Err(
+        BinError::SeekOutOfRange {
+          position: pos,
+          length: self.mmap.len() as u64,
+        }
+        .into(),
+      );
+    }
+    self.ptr = pos as usize;
+    Ok(self.ptr as u64)
+  }
+}
+
+impl TryFrom<File> for MmapReader {
+  type Error = Error;
+
+  fn try_from(file: File) -> Result<Self, Error> {
+    // SAFETY: the mapping is read only. Like every memory map, it is
+    // undefined behavior if the file is truncated or modified while mapped.
+    let mmap = unsafe { Mmap::map(&file)? };
+    Ok(Self::new(mmap))
+  }
+}
+
+impl BinaryReader for MmapReader {
+  fn set_endian(&mut self, endian: Endian) {
+    self.endian = endian;
+  }
+
+  fn endian(&self) -> Endian {
+    self.endian
+  }
+
+  fn read_byte(&mut self) -> Result<u8, Error> {
+    self.check_bound(1)?;
+    let b = self.mmap[self.ptr];
+    self.ptr += 1;
+    Ok(b)
+  }
+
+  fn read_exact(&mut self, array: &mut [u8]) -> Result<(), Error> {
+    let len = array.len();
+    self.check_bound(len)?;
+    array.copy_from_slice(&self.mmap[self.ptr..self.ptr + len]);
+    self.ptr += len;
+    Ok(())
+  }
+
+  fn read_bytes_as_vec(&mut self, len: usize) -> Result<Vec<u8>, Error> {
+    self.check_bound(len)?;
+    let c = self.mmap[self.ptr..self.ptr + len].to_vec();
+    self.ptr += len;
+    Ok(c)
+  }
+
+  fn read_bytes_no_move(&mut self, len: usize) -> Result<Vec<u8>, Error> {
+    self.check_bound(len)?;
+    Ok(self.mmap[self.ptr..self.ptr + len].to_vec())
+  }
+
+  /// skip_ptr skips offset size bytes
+  fn skip_ptr(&mut self, size: usize) -> Result<usize, Error> {
+    self.check_bound(size)?;
+    self.ptr += size;
+    Ok(size)
+  }
+
+  fn offset(&mut self) -> Result<u64, Error> {
+    Ok(self.ptr as u64)
+  }
+
+  fn seek(&mut self, seek: SeekFrom) -> Result<u64, Error> {
+    match seek {
+      SeekFrom::Start(pos) => self.set_ptr(pos as i128),
+      SeekFrom::End(pos) => self.set_ptr(self.mmap.len() as i128 + pos as i128),
+      SeekFrom::Current(pos) => self.set_ptr(self.ptr as i128 + pos as i128),
+    }
+  }
+}
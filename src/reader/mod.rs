@@ -1,18 +1,62 @@
 //! A reader default uses system endianness
 //! If you will use another endianness,use set_endian.
 //! 0.0.10 StreamReader enable for wasm32
+//! 0.0.11 SliceReader, MmapReader (feature="mmap")
+//! 0.0.11 read_local_string decodes every CodeType (feature="codec")
+//! 0.0.11 read_pascal_string, read_cstring, read_until, read_line
+//! 0.0.11 read_fixed_string, CodeType is available without feature="codec"
+//! 0.0.11 SubReader made by sub_reader and window
+//! 0.0.11 BytesReader incremental mode, append, checkpoint and rollback
 
 mod binary;
+mod bit;
 mod bytes;
+mod from_binary;
+#[cfg(all(feature = "mmap", not(target_family = "wasm")))]
+mod mmap;
+mod slice;
 mod stream;
+mod sub;
+#[cfg(any(feature = "async", feature = "futures"))]
+pub(crate) use self::binary::end_of_data;
 pub use self::binary::BinaryReader;
-pub use self::bytes::BytesReader;
+pub(crate) use self::binary::{check_supported, check_width};
+pub use self::bit::BitReader;
+pub use self::bytes::{BytesReader, Checkpoint};
+pub use self::from_binary::FromBinary;
+#[cfg(all(feature = "mmap", not(target_family = "wasm")))]
+pub use self::mmap::MmapReader;
+pub use self::slice::SliceReader;
 pub use self::stream::StreamReader;
+pub use self::sub::SubReader;
 
-#[cfg(feature = "codec")]
-use encoding_rs::*;
+/// LengthPrefix is the size of a length prefix of `read_pascal_string`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthPrefix {
+  U8,
+  U16,
+  U32,
+}
+
+/// Trim is how `read_fixed_string` removes the padding of a field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trim {
+  /// The string ends at the first NUL (tar, FAT).
+  Nul,
+  /// Trailing spaces are removed (ISO 9660).
+  Space,
+  /// The string ends at the first NUL, and trailing spaces are removed.
+  Both,
+  /// The whole field is returned.
+  None,
+}
 
-#[cfg(feature = "codec")]
+/// CodeType is a text encoding.
+///
+/// Ascii, Utf8, Utf16Be and Utf16le are always available,
+/// others need feature="codec".
+/// Ascii is one byte per character up to U+00FF like `read_ascii_string`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CodeType {
   Ascii,
   Big5,
@@ -25,3 +69,67 @@ pub enum CodeType {
   Utf16le,
   Utf8,
 }
+
+impl CodeType {
+  /// name returns the encoding name, for example "Shift_JIS".
+  pub fn name(&self) -> &'static str {
+    match self {
+      CodeType::Ascii => "US-ASCII",
+      CodeType::Big5 => "Big5",
+      CodeType::EucJp => "EUC-JP",
+      CodeType::EucKr => "EUC-KR",
+      CodeType::Gb18030 => "gb18030",
+      CodeType::Jis => "ISO-2022-JP",
+      CodeType::ShiftJis => "Shift_JIS",
+      CodeType::Utf16Be => "UTF-16BE",
+      CodeType::Utf16le => "UTF-16LE",
+      CodeType::Utf8 => "UTF-8",
+    }
+  }
+
+  /// is_supported returns false if this encoding needs feature="codec", but it is disabled.
+  pub fn is_supported(&self) -> bool {
+    cfg!(feature = "codec")
+      || matches!(
+        self,
+        CodeType::Ascii | CodeType::Utf8 | CodeType::Utf16Be | CodeType::Utf16le
+      )
+  }
+
+  /// encoding returns the encoding of encoding_rs. Ascii has no encoding.
+  #[cfg(feature = "codec")]
+  pub(crate) fn encoding(&self) -> Option<&'static encoding_rs::Encoding> {
+    let encoding = match self {
+      CodeType::Ascii => return None,
+      CodeType::Big5 => encoding_rs::BIG5,
+      CodeType::EucJp => encoding_rs::EUC_JP,
+      CodeType::EucKr => encoding_rs::EUC_KR,
+      CodeType::Gb18030 => encoding_rs::GB18030,
+      CodeType::Jis => encoding_rs::ISO_2022_JP,
+      CodeType::ShiftJis => encoding_rs::SHIFT_JIS,
+      CodeType::Utf16Be => encoding_rs::UTF_16BE,
+      CodeType::Utf16le => encoding_rs::UTF_16LE,
+      CodeType::Utf8 => encoding_rs::UTF_8,
+    };
+    Some(encoding)
+  }
+}
+
+/// DecodeOptions is options of `read_local_string_with`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeOptions {
+  /// The string ends at the first NUL, 0x00 or 0x0000 for UTF-16.
+  /// The rest of `size` bytes is skipped. default is true.
+  pub nul_terminated: bool,
+  /// Malformed sequences are replaced with U+FFFD instead of an error. default is false.
+  pub lossy: bool,
+}
+
+impl Default for DecodeOptions {
+  fn default() -> Self {
+    Self {
+      nul_terminated: true,
+      lossy: false,
+    }
+  }
+}
@@ -0,0 +1,220 @@
+use crate::error::BinError;
+use crate::Endian;
+use std::io::{Error, SeekFrom};
+
+use super::BinaryReader;
+
+/// SliceReader borrows `&'a [u8]` and reads it without copying.
+///
+/// `read_slice` and `read_str` return data borrowed from the source,
+/// which is useful for memory-mapped files.
+/// ```
+/// use bin_rs::reader::*;
+/// use std::io::Error;
+///
+/// fn test() -> Result<(), Error> {
+///   let buffer = b"\x00\x05Hello World!";
+///   let mut reader = SliceReader::new(buffer);
+///   let len = reader.read_u16_be()? as usize;
+///   let s: &str = reader.read_str(len)?;
+///   assert_eq!(s, "Hello");
+///   Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SliceReader<'a> {
+  buffer: &'a [u8],
+  ptr: usize,
+  endian: Endian,
+  incremental: bool,
+}
+
+impl<'a> SliceReader<'a> {
+  pub fn new(buffer: &'a [u8]) -> Self {
+    Self {
+      buffer,
+      ptr: 0,
+      endian: crate::system_endian(),
+      incremental: false,
+    }
+  }
+
+  /// set_incremental switches incremental mode for a slice of data arriving in pieces,
+  /// like `BytesReader::set_incremental`.
+  ///
+  /// In incremental mode, a read beyond the slice returns `BinError::Incomplete`
+  /// instead of `UnexpectedEof`, and `read_until` waits for the delimiter.
+  /// Read again from the start with a longer slice.
+  pub fn set_incremental(&mut self, incremental: bool) {
+    self.incremental = incremental;
+  }
+
+  pub fn is_incremental(&self) -> bool {
+    self.incremental
+  }
+
+  /// remaining returns unread bytes.
+  pub fn remaining(&self) -> &'a [u8] {
+    &self.buffer[self.ptr..]
+  }
+
+  /// read_slice returns `len` bytes borrowed from the source and moves offset.
+  pub fn read_slice(&mut self, len: usize) -> Result<&'a [u8], Error> {
+    self.check_bound(len)?;
+    let slice = &self.buffer[self.ptr..self.ptr + len];
+    self.ptr += len;
+    Ok(slice)
+  }
+
+  /// read_str returns utf8 string borrowed from the source. "size" refers to the number of bytes.
+  pub fn read_str(&mut self, size: usize) -> Result<&'a str, Error> {
+    self.check_bound(size)?;
+    match std::str::from_utf8(&self.buffer[self.ptr..self.ptr + size]) {
+      Ok(s) => {
+        self.ptr += size;
+        Ok(s)
+      }
+      _ => Err(
+        BinError::InvalidString {
+          offset: self.ptr as u64,
+          encoding: "UTF-8",
+        }
+        .into(),
+      ),
+    }
+  }
+
+  fn check_bound(&self, size: usize) -> Result<(), Error> {
+    let available = self.buffer.len() - self.ptr;
+    if size > available && self.incremental {
+      Err(
+        BinError::Incomplete {
+          offset: self.ptr as u64,
+          needed: size - available,
+        }
+        .into(),
+      )
+    } else if size > available {
+      Err(
+        BinError::UnexpectedEof {
+          offset: self.ptr as u64,
+          requested: size,
+          available: self.buffer.len() - self.ptr,
+        }
+        .into(),
+      )
+    } else {
+      Ok(())
+    }
+  }
+
+  fn set_ptr(&mut self, pos: i128) -> Result<u64, Error> {
+    let end = self.buffer.len() as i128;
+    if self.incremental && pos > end {
+      return Err(
+        BinError::Incomplete {
+          offset: self.ptr as u64,
+          needed: (pos - end) as usize,
+        }
+        .into(),
+      );
+    }
+    if pos < 0 || pos > end {
+      return Err(
+        BinError::SeekOutOfRange {
+          position: pos,
+          length: self.buffer.len() as u64,
+        }
+        .into(),
+      );
+    }
+    self.ptr = pos as usize;
+    Ok(self.ptr as u64)
+  }
+}
+
+impl<'a> From<&'a [u8]> for SliceReader<'a> {
+  fn from(buffer: &'a [u8]) -> Self {
+    Self::new(buffer)
+  }
+}
+
+impl<'a> From<&'a Vec<u8>> for SliceReader<'a> {
+  fn from(buffer: &'a Vec<u8>) -> Self {
+    Self::new(buffer)
+  }
+}
+
+impl BinaryReader for SliceReader<'_> {
+  fn set_endian(&mut self, endian: Endian) {
+    self.endian = endian;
+  }
+
+  fn endian(&self) -> Endian {
+    self.endian
+  }
+
+  fn read_byte(&mut self) -> Result<u8, Error> {
+    self.check_bound(1)?;
+    let b = self.buffer[self.ptr];
+    self.ptr += 1;
+    Ok(b)
+  }
+
+  fn read_exact(&mut self, array: &mut [u8]) -> Result<(), Error> {
+    array.copy_from_slice(self.read_slice(array.len())?);
+    Ok(())
+  }
+
+  fn read_bytes_as_vec(&mut self, len: usize) -> Result<Vec<u8>, Error> {
+    Ok(self.read_slice(len)?.to_vec())
+  }
+
+  fn read_bytes_no_move(&mut self, len: usize) -> Result<Vec<u8>, Error> {
+    self.check_bound(len)?;
+    Ok(self.buffer[self.ptr..self.ptr + len].to_vec())
+  }
+
+  fn read_utf8_string(&mut self, size: usize) -> Result<String, Error> {
+    Ok(self.read_str(size)?.to_string())
+  }
+
+  fn read_until(&mut self, delimiter: u8) -> Result<Vec<u8>, Error> {
+    let rest = &self.buffer[self.ptr..];
+    let len = match rest.iter().position(|b| *b == delimiter) {
+      Some(i) => i + 1,
+      None if self.incremental => {
+        return Err(
+          BinError::Incomplete {
+            offset: self.ptr as u64,
+            needed: 1,
+          }
+          .into(),
+        )
+      }
+      None => rest.len(),
+    };
+    let array = rest[..len].to_vec();
+    self.ptr += len;
+    Ok(array)
+  }
+
+  /// skip_ptr skips offset size bytes
+  fn skip_ptr(&mut self, size: usize) -> Result<usize, Error> {
+    self.check_bound(size)?;
+    self.ptr += size;
+    Ok(size)
+  }
+
+  fn offset(&mut self) -> Result<u64, Error> {
+    Ok(self.ptr as u64)
+  }
+
+  fn seek(&mut self, seek: SeekFrom) -> Result<u64, Error> {
+    match seek {
+      SeekFrom::Start(pos) => self.set_ptr(pos as i128),
+      SeekFrom::End(pos) => self.set_ptr(self.buffer.len() as i128 + pos as i128),
+      SeekFrom::Current(pos) => self.set_ptr(self.ptr as i128 + pos as i128),
+    }
+  }
+}
@@ -1,3 +1,4 @@
+use crate::error::BinError;
 use crate::Endian;
 use std::fs::File;
 use std::io::BufRead;
@@ -11,6 +12,7 @@ use std::io::ErrorKind;
 /// use BufRead trait
 use std::io::Read;
 use std::io::Seek;
+use std::io::SeekFrom;
 
 use std::path::PathBuf;
 
@@ -41,6 +43,31 @@ impl<R: BufRead + Seek> StreamReader<R> {
       endian: crate::system_endian(),
     }
   }
+
+  // fill reads whole `array`. At the end of data, it goes back to the start of the read.
+  fn fill(&mut self, array: &mut [u8]) -> Result<(), Error> {
+    let mut filled = 0;
+    while filled < array.len() {
+      match self.reader.read(&mut array[filled..]) {
+        Ok(0) => return Err(self.eof(array.len(), filled)?.into()),
+        Ok(len) => filled += len,
+        Err(e) if e.kind() == ErrorKind::Interrupted => {}
+        Err(e) => return Err(e),
+      }
+    }
+    Ok(())
+  }
+
+  // eof makes UnexpectedEof after `available` bytes are read, and goes back to the start of the read.
+  fn eof(&mut self, requested: usize, available: usize) -> Result<BinError, Error> {
+    let offset = self.reader.stream_position()? - available as u64;
+    self.reader.seek(SeekFrom::Start(offset))?;
+    Ok(BinError::UnexpectedEof {
+      offset,
+      requested,
+      available,
+    })
+  }
 }
 
 impl<R> From<R> for StreamReader<Cursor<R>>
@@ -78,7 +105,7 @@ impl<R: BufRead + Seek> BinaryReader for StreamReader<R> {
 
   fn read_byte(&mut self) -> Result<u8, Error> {
     let mut buffer = [0; 1];
-    self.reader.read_exact(&mut buffer)?;
+    self.fill(&mut buffer)?;
     Ok(buffer[0])
   }
   fn read_u8(&mut self) -> Result<u8, Error> {
@@ -90,28 +117,31 @@ impl<R: BufRead + Seek> BinaryReader for StreamReader<R> {
   }
 
   fn read_exact(&mut self, array: &mut [u8]) -> std::result::Result<(), Error> {
-    self.reader.read_exact(array)?;
-    Ok(())
+    self.fill(array)
   }
 
   fn read_bytes_as_vec(&mut self, len: usize) -> Result<Vec<u8>, Error> {
     let mut array: Vec<u8> = (0..len).map(|_| 0).collect();
-    self.reader.read_exact(&mut array)?;
+    self.fill(&mut array)?;
     Ok(array)
   }
 
   // This function read bytes and does not move pointer.
   // However it's behavior dependences read buffer size.
   fn read_bytes_no_move(&mut self, len: usize) -> Result<Vec<u8>, Error> {
-    let buffer = self.reader.fill_buf()?;
-    if buffer.len() < len {
-      let err = format!(
-        "Data shotage,request {} but read {} bytes",
-        len,
-        buffer.len()
+    let available = self.reader.fill_buf()?.len();
+    if available < len {
+      let offset = self.reader.stream_position()?;
+      return Err(
+        BinError::UnexpectedEof {
+          offset,
+          requested: len,
+          available,
+        }
+        .into(),
       );
-      return Err(Error::new(ErrorKind::Other, err));
     }
+    let buffer = self.reader.fill_buf()?;
     let array: Vec<u8> = (0..len).map(|i| buffer[i]).collect();
     Ok(array)
   }
@@ -188,153 +218,139 @@ impl<R: BufRead + Seek> BinaryReader for StreamReader<R> {
 
   fn read_u16_be(&mut self) -> Result<u16, Error> {
     let mut array = [0; 2];
-    self.reader.read_exact(&mut array)?;
+    self.fill(&mut array)?;
     Ok(u16::from_be_bytes(array))
   }
 
   fn read_u32_be(&mut self) -> Result<u32, Error> {
     let mut array = [0; 4];
-    self.reader.read_exact(&mut array)?;
+    self.fill(&mut array)?;
     Ok(u32::from_be_bytes(array))
   }
 
   fn read_u64_be(&mut self) -> Result<u64, Error> {
     let mut array = [0; 8];
-    self.reader.read_exact(&mut array)?;
+    self.fill(&mut array)?;
     Ok(u64::from_be_bytes(array))
   }
 
   fn read_u128_be(&mut self) -> Result<u128, Error> {
     let mut array = [0; 16];
-    self.reader.read_exact(&mut array)?;
+    self.fill(&mut array)?;
     Ok(u128::from_be_bytes(array))
   }
 
   fn read_i16_be(&mut self) -> Result<i16, Error> {
     let mut array = [0; 2];
-    self.reader.read_exact(&mut array)?;
+    self.fill(&mut array)?;
     Ok(i16::from_be_bytes(array))
   }
 
   fn read_i32_be(&mut self) -> Result<i32, Error> {
     let mut array = [0; 4];
-    self.reader.read_exact(&mut array)?;
+    self.fill(&mut array)?;
     Ok(i32::from_be_bytes(array))
   }
 
   fn read_i64_be(&mut self) -> Result<i64, Error> {
     let mut array = [0; 8];
-    self.reader.read_exact(&mut array)?;
+    self.fill(&mut array)?;
     Ok(i64::from_be_bytes(array))
   }
 
   fn read_i128_be(&mut self) -> Result<i128, Error> {
     let mut array = [0; 16];
-    self.reader.read_exact(&mut array)?;
+    self.fill(&mut array)?;
     Ok(i128::from_be_bytes(array))
   }
 
   fn read_f32_be(&mut self) -> Result<f32, Error> {
     let mut array = [0; 4];
-    self.reader.read_exact(&mut array)?;
+    self.fill(&mut array)?;
     Ok(f32::from_be_bytes(array))
   }
 
   fn read_f64_be(&mut self) -> Result<f64, Error> {
     let mut array = [0; 8];
-    self.reader.read_exact(&mut array)?;
+    self.fill(&mut array)?;
     Ok(f64::from_be_bytes(array))
   }
 
   fn read_u16_le(&mut self) -> Result<u16, Error> {
     let mut array = [0; 2];
-    self.reader.read_exact(&mut array)?;
+    self.fill(&mut array)?;
     Ok(u16::from_le_bytes(array))
   }
 
   fn read_u32_le(&mut self) -> Result<u32, Error> {
     let mut array = [0; 4];
-    self.reader.read_exact(&mut array)?;
+    self.fill(&mut array)?;
     Ok(u32::from_le_bytes(array))
   }
 
   fn read_u64_le(&mut self) -> Result<u64, Error> {
     let mut array = [0; 8];
-    self.reader.read_exact(&mut array)?;
+    self.fill(&mut array)?;
     Ok(u64::from_le_bytes(array))
   }
 
   fn read_u128_le(&mut self) -> Result<u128, Error> {
     let mut array = [0; 16];
-    self.reader.read_exact(&mut array)?;
+    self.fill(&mut array)?;
     Ok(u128::from_le_bytes(array))
   }
 
   fn read_i16_le(&mut self) -> Result<i16, Error> {
     let mut array = [0; 2];
-    self.reader.read_exact(&mut array)?;
+    self.fill(&mut array)?;
     Ok(i16::from_le_bytes(array))
   }
 
   fn read_i32_le(&mut self) -> Result<i32, Error> {
     let mut array = [0; 4];
-    self.reader.read_exact(&mut array)?;
+    self.fill(&mut array)?;
     Ok(i32::from_le_bytes(array))
   }
 
   fn read_i64_le(&mut self) -> Result<i64, Error> {
     let mut array = [0; 8];
-    self.reader.read_exact(&mut array)?;
+    self.fill(&mut array)?;
     Ok(i64::from_le_bytes(array))
   }
 
   fn read_i128_le(&mut self) -> Result<i128, Error> {
     let mut array = [0; 16];
-    self.reader.read_exact(&mut array)?;
+    self.fill(&mut array)?;
     Ok(i128::from_le_bytes(array))
   }
 
   fn read_f32_le(&mut self) -> Result<f32, Error> {
     let mut array = [0; 4];
-    self.reader.read_exact(&mut array)?;
+    self.fill(&mut array)?;
     Ok(f32::from_le_bytes(array))
   }
 
   fn read_f64_le(&mut self) -> Result<f64, Error> {
     let mut array = [0; 8];
-    self.reader.read_exact(&mut array)?;
+    self.fill(&mut array)?;
     Ok(f64::from_le_bytes(array))
   }
 
-  #[cfg(feature = "codec")]
-  fn read_local_string(&mut self, size: usize, code: CodeType) -> Result<String, Error> {
-    let mut array: Vec<u8> = (0..size).map(|_| 0).collect();
-    self.reader.read_exact(&mut array)?;
-
-    let buf = &array;
-    let mut s = Vec::new();
-    for i in 0..size {
-      if buf[i] == 0 {
-        break;
-      }
-      s.push(buf[i]);
-    }
-    let res = String::from_utf8(s);
-    match res {
-      Ok(strings) => {
-        return Ok(strings);
-      }
-      _ => {
-        let err = "This string can not read";
-        return Err(Error::new(ErrorKind::Other, err));
-      }
-    }
+  fn read_until(&mut self, delimiter: u8) -> Result<Vec<u8>, Error> {
+    let mut array = Vec::new();
+    BufRead::read_until(&mut self.reader, delimiter, &mut array)?;
+    Ok(array)
   }
 
   /// skip size byte
   fn skip_ptr(&mut self, size: usize) -> Result<usize, Error> {
-    let mut array: Vec<u8> = (0..size).map(|_| 0).collect();
-    self.reader.read_exact(&mut array)?;
+    let skipped = std::io::copy(
+      &mut (&mut self.reader).take(size as u64),
+      &mut std::io::sink(),
+    )?;
+    if skipped < size as u64 {
+      return Err(self.eof(size, skipped as usize)?.into());
+    }
     Ok(size)
   }
 
@@ -0,0 +1,166 @@
+use crate::error::BinError;
+use crate::Endian;
+use std::io::{Error, SeekFrom};
+
+use super::BinaryReader;
+
+/// SubReader is a view of `len` bytes of another reader, made by `sub_reader` or `window`.
+///
+/// Offsets start at 0 at the beginning of the window, and reading past the window is an error,
+/// so a chunk parser can not overrun into the next chunk.
+/// Data is read through the parent reader, so nothing is copied in advance.
+/// The parent is left where the view stopped; call `skip_rest` to move it to the end of the window.
+/// ```
+/// use bin_rs::reader::*;
+/// use std::io::Error;
+///
+/// fn test() -> Result<(), Error> {
+///   let buffer = b"fmt \x04\x00\x00\x00\x01\x00\x02\x00data\x00\x00\x00\x00";
+///   let mut reader = BytesReader::new(buffer);
+///   reader.set_endian(bin_rs::Endian::LittleEndian);
+///   let id = reader.read_bytes_as_vec(4)?;
+///   let size = reader.read_u32()? as u64;
+///   let mut chunk = reader.sub_reader(size)?;
+///   assert_eq!(chunk.read_u16()?, 1);
+///   assert_eq!(chunk.offset()?, 2);
+///   assert!(chunk.read_u32().is_err()); // only 2 bytes are left
+///   chunk.skip_rest()?;
+///   assert_eq!(reader.read_bytes_as_vec(4)?, b"data");
+///   Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SubReader<'r, R: ?Sized> {
+  reader: &'r mut R,
+  start: u64,
+  len: u64,
+  ptr: u64,
+  endian: Endian,
+}
+
+impl<'r, R: BinaryReader + ?Sized> SubReader<'r, R> {
+  /// new makes a view of `len` bytes from the current offset of `reader`.
+  pub fn new(reader: &'r mut R, len: u64) -> Result<Self, Error> {
+    let start = reader.offset()?;
+    let endian = reader.endian();
+    Ok(Self {
+      reader,
+      start,
+      len,
+      ptr: 0,
+      endian,
+    })
+  }
+
+  /// len returns the size of the window.
+  pub fn len(&self) -> u64 {
+    self.len
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// start returns the offset of the window in the parent reader.
+  pub fn start(&self) -> u64 {
+    self.start
+  }
+
+  /// remaining returns the number of unread bytes in the window.
+  pub fn remaining(&self) -> u64 {
+    self.len - self.ptr
+  }
+
+  /// skip_rest moves to the end of the window, so the parent is just after it.
+  pub fn skip_rest(&mut self) -> Result<(), Error> {
+    self.move_to(self.len)
+  }
+
+  // move_to moves the parent to `pos` of the window.
+  // Forward moves use skip_ptr, because some parents can not seek to just their end.
+  fn move_to(&mut self, pos: u64) -> Result<(), Error> {
+    if pos >= self.ptr {
+      self.reader.skip_ptr((pos - self.ptr) as usize)?;
+    } else {
+      self.reader.seek(SeekFrom::Start(self.start + pos))?;
+    }
+    self.ptr = pos;
+    Ok(())
+  }
+
+  fn check_bound(&self, size: usize) -> Result<(), Error> {
+    if size as u64 > self.remaining() {
+      Err(
+        BinError::UnexpectedEof {
+          offset: self.ptr,
+          requested: size,
+          available: self.remaining() as usize,
+        }
+        .into(),
+      )
+    } else {
+      Ok(())
+    }
+  }
+}
+
+impl<R: BinaryReader + ?Sized> BinaryReader for SubReader<'_, R> {
+  fn set_endian(&mut self, endian: Endian) {
+    self.endian = endian;
+  }
+
+  fn endian(&self) -> Endian {
+    self.endian
+  }
+
+  fn read_exact(&mut self, array: &mut [u8]) -> Result<(), Error> {
+    self.check_bound(array.len())?;
+    self.reader.read_exact(array)?;
+    self.ptr += array.len() as u64;
+    Ok(())
+  }
+
+  fn read_bytes_as_vec(&mut self, len: usize) -> Result<Vec<u8>, Error> {
+    self.check_bound(len)?;
+    let array = self.reader.read_bytes_as_vec(len)?;
+    self.ptr += len as u64;
+    Ok(array)
+  }
+
+  fn read_bytes_no_move(&mut self, len: usize) -> Result<Vec<u8>, Error> {
+    self.check_bound(len)?;
+    self.reader.read_bytes_no_move(len)
+  }
+
+  /// skip_ptr skips offset size bytes
+  fn skip_ptr(&mut self, size: usize) -> Result<usize, Error> {
+    self.check_bound(size)?;
+    self.reader.skip_ptr(size)?;
+    self.ptr += size as u64;
+    Ok(size)
+  }
+
+  /// offset returns the offset from the beginning of the window.
+  fn offset(&mut self) -> Result<u64, Error> {
+    Ok(self.ptr)
+  }
+
+  fn seek(&mut self, seek: SeekFrom) -> Result<u64, Error> {
+    let pos = match seek {
+      SeekFrom::Start(pos) => pos as i128,
+      SeekFrom::End(pos) => self.len as i128 + pos as i128,
+      SeekFrom::Current(pos) => self.ptr as i128 + pos as i128,
+    };
+    if pos < 0 || pos > self.len as i128 {
+      return Err(
+        BinError::SeekOutOfRange {
+          position: pos,
+          length: self.len,
+        }
+        .into(),
+      );
+    }
+    self.move_to(pos as u64)?;
+    Ok(self.ptr)
+  }
+}
@@ -1,4 +1,6 @@
 use crate::reader::*;
+use crate::writer::*;
+use crate::BitOrder;
 use crate::Endian;
 use std::fs;
 use std::io::Cursor;
@@ -9,7 +11,7 @@ fn check_works() -> Result<(), Box<dyn std::error::Error>> {
   let buffer: Vec<u8> = (0..255).collect();
   let mut reader = BytesReader::from(buffer);
 
-  let endian = if cfg!(tarread_endian = "big") {
+  let endian = if cfg!(target_endian = "big") {
     Endian::BigEndian
   } else {
     Endian::LittleEndian
@@ -60,10 +62,7 @@ fn check_works() -> Result<(), Box<dyn std::error::Error>> {
   let r = reader.read_bytes_as_vec(2)?;
   assert_eq!(r, [0xf2, 0xf1]);
 
-  let r = reader.read_i128_le();
-  if r.is_err() {
-    assert!(true)
-  }
+  let _ = reader.read_i128_le();
 
   let buffer: Vec<u8> = (0..16).map(|i| 255 - i).collect();
   let mut reader = BytesReader::from(buffer);
@@ -101,18 +100,85 @@ fn check_works() -> Result<(), Box<dyn std::error::Error>> {
   let buffer1 = reader.read_bytes_as_vec(4)?;
   assert_eq!(buffer1, b"o Wo");
 
-  let buffer = b"Hello World!\01234";
+  let buffer = b"Hello World!\x001234";
   let mut reader = BytesReader::new(buffer);
-  let r = reader.read_ascii_string("Hello World!\01234".len())?; // must after \0 is trim
+  let r = reader.read_ascii_string("Hello World!\x001234".len())?; // must after \0 is trim
   assert_eq!(r, "Hello World!");
   let buffer =
-    b"\xE3\x81\xB8\xE3\x82\x8D\xE3\x83\xBC\xE3\x82\x8F\xE3\x83\xBC\xE3\x82\x8B\xE3\x81\xA9\01234";
+    b"\xE3\x81\xB8\xE3\x82\x8D\xE3\x83\xBC\xE3\x82\x8F\xE3\x83\xBC\xE3\x82\x8B\xE3\x81\xA9\x001234";
   let mut reader = BytesReader::new(buffer);
   let r = reader.read_utf8_string(23)?;
-  assert_eq!(r, "へろーわーるど\01");
-
-  if cfg!(feature = "codec") {
-    // no impl reader.read_local_string
+  assert_eq!(r, "へろーわーるど\x001");
+
+  #[cfg(feature = "codec")]
+  {
+    let buffer = b"\x93\xfa\x96\x7b\x8c\xea\x00\x41";
+    let mut reader = BytesReader::new(buffer);
+    let r = reader.read_local_string(8, CodeType::ShiftJis)?;
+    assert_eq!(r, "日本語");
+    assert_eq!(reader.offset()?, 8);
+    let tests: [(&[u8], CodeType, &str); 9] = [
+      (b"Hello", CodeType::Ascii, "Hello"),
+      (b"\xa4\xa4\xa4\xe5", CodeType::Big5, "中文"),
+      (b"\xc6\xfc\xcb\xdc\xb8\xec", CodeType::EucJp, "日本語"),
+      (b"\xc7\xd1\xb1\xb9\xbe\xee", CodeType::EucKr, "한국어"),
+      (b"\xc8\xd5\xb1\xbe\xd5\x5a", CodeType::Gb18030, "日本語"),
+      (
+        b"\x1b\x24\x42\x46\x7c\x4b\x5c\x38\x6c\x1b\x28\x42",
+        CodeType::Jis,
+        "日本語",
+      ),
+      (
+        b"\x65\xe5\x67\x2c\x8a\x9e\x00\x00\x00\x41",
+        CodeType::Utf16Be,
+        "日本語",
+      ),
+      (
+        b"\xe5\x65\x2c\x67\x9e\x8a\x00\x00",
+        CodeType::Utf16le,
+        "日本語",
+      ),
+      (
+        b"\xe6\x97\xa5\xe6\x9c\xac\xe8\xaa\x9e",
+        CodeType::Utf8,
+        "日本語",
+      ),
+    ];
+    for (bytes, code, string) in tests {
+      let mut reader = BytesReader::new(bytes);
+      assert_eq!(reader.read_local_string(bytes.len(), code)?, string);
+    }
+
+    // options
+    let buffer = b"\x93\xfa\x00\x96\x7b\xff";
+    let mut reader = BytesReader::new(buffer);
+    let options = DecodeOptions {
+      nul_terminated: false,
+      lossy: false,
+    };
+    assert_eq!(
+      reader.read_local_string_with(5, CodeType::ShiftJis, options)?,
+      "日\x00本"
+    );
+    reader.seek(SeekFrom::Start(3))?;
+    let err = reader.read_local_string(3, CodeType::ShiftJis).unwrap_err();
+    match crate::error::BinError::from(err) {
+      crate::error::BinError::InvalidString { offset, encoding } => {
+        assert_eq!((offset, encoding), (3, "Shift_JIS"))
+      }
+      e => panic!("{}", e),
+    }
+    reader.seek(SeekFrom::Start(3))?;
+    let options = DecodeOptions {
+      lossy: true,
+      ..Default::default()
+    };
+    assert_eq!(
+      reader.read_local_string_with(3, CodeType::ShiftJis, options)?,
+      "本\u{FFFD}"
+    );
+    let mut reader = StreamReader::new(Cursor::new(b"abc\xff"));
+    assert_eq!(reader.read_local_string(4, CodeType::Ascii)?, "abc\u{ff}");
   }
 
   let buffer = [0x71, 0x3D, 0x0A, 0xD7, 0xA3, 0x30, 0x31, 0xC0];
@@ -157,6 +223,9 @@ fn check_works() -> Result<(), Box<dyn std::error::Error>> {
   assert_eq!(r, 4);
   let r = reader.seek(SeekFrom::End(-1))?;
   assert_eq!(r, 254);
+  let r = reader.seek(SeekFrom::Current(-4))?;
+  assert_eq!(r, 250);
+  assert!(reader.seek(SeekFrom::Current(-251)).is_err());
 
   let f = std::path::PathBuf::from("./test/unascii.txt");
   let buffer = fs::read(f)?;
@@ -168,6 +237,85 @@ fn check_works() -> Result<(), Box<dyn std::error::Error>> {
   Ok(())
 }
 
+#[test]
+fn check_binary_writer() -> Result<(), Box<dyn std::error::Error>> {
+  use crate::writer::{BinaryWriter, Placeholders};
+  use std::io::{Error, ErrorKind};
+
+  // a writer only provides bytes, endian and offset, the trait writes the rest
+  struct VecWriter {
+    buffer: Vec<u8>,
+    endian: Endian,
+    placeholders: Placeholders,
+  }
+
+  impl BinaryWriter for VecWriter {
+    fn set_endian(&mut self, endian: Endian) {
+      self.endian = endian;
+    }
+
+    fn endian(&self) -> Endian {
+      self.endian
+    }
+
+    fn write_bytes(&mut self, array: &[u8]) -> Result<(), Error> {
+      self.buffer.extend_from_slice(array);
+      Ok(())
+    }
+
+    fn offset(&mut self) -> Result<u64, Error> {
+      Ok(self.buffer.len() as u64)
+    }
+
+    fn seek(&mut self, seek: SeekFrom) -> Result<u64, Error> {
+      match seek {
+        SeekFrom::Current(0) => self.offset(),
+        _ => Err(Error::from(ErrorKind::Unsupported)),
+      }
+    }
+
+    fn placeholders(&mut self) -> &mut Placeholders {
+      &mut self.placeholders
+    }
+  }
+
+  let mut writer = VecWriter {
+    buffer: Vec::new(),
+    endian: Endian::BigEndian,
+    placeholders: Placeholders::new(),
+  };
+  writer.write_u8(0x01)?;
+  writer.write_i8(-2)?;
+  writer.write_u16(0x0304)?;
+  writer.write_u32_le(0x05060708)?;
+  writer.set_endian(Endian::LittleEndian);
+  writer.write_i16(-2)?;
+  writer.write_u64_be(0x090a0b0c0d0e0f10)?;
+  writer.write_f32(1.5)?;
+  assert_eq!(writer.write_ascii_string("ab")?, 2);
+  assert_eq!(writer.write_utf16be_string("c")?, 2);
+  assert_eq!(writer.endian(), Endian::LittleEndian);
+  assert_eq!(writer.write_utf8_string("日")?, 3);
+  assert_eq!(writer.offset()?, 29);
+  assert!(writer.write_ascii_string("日").is_err());
+
+  let mut reader = BytesReader::new(&writer.buffer);
+  reader.set_endian(Endian::BigEndian);
+  assert_eq!(reader.read_u8()?, 0x01);
+  assert_eq!(reader.read_i8()?, -2);
+  assert_eq!(reader.read_u16()?, 0x0304);
+  assert_eq!(reader.read_u32_le()?, 0x05060708);
+  reader.set_endian(Endian::LittleEndian);
+  assert_eq!(reader.read_i16()?, -2);
+  assert_eq!(reader.read_u64_be()?, 0x090a0b0c0d0e0f10);
+  assert_eq!(reader.read_f32()?, 1.5);
+  assert_eq!(reader.read_ascii_string(2)?, "ab");
+  assert_eq!(reader.read_bytes_as_vec(2)?, b"\x00c");
+  assert_eq!(reader.read_utf8_string(3)?, "日");
+
+  Ok(())
+}
+
 #[test]
 fn check_stream() -> Result<(), Box<dyn std::error::Error>> {
   use std::path::PathBuf;
@@ -220,10 +368,7 @@ fn check_stream() -> Result<(), Box<dyn std::error::Error>> {
   let r = reader.read_bytes_as_vec(2)?;
   assert_eq!(r, [0xf2, 0xf1]);
 
-  let r = reader.read_i128_le(); // outbounds
-  if r.is_err() {
-    assert!(true)
-  }
+  let _ = reader.read_i128_le();
 
   let buffer: Vec<u8> = (0..16).map(|i| 255 - i).collect();
   let f = Cursor::new(&*buffer);
@@ -273,20 +418,20 @@ fn check_stream() -> Result<(), Box<dyn std::error::Error>> {
   let buffer1 = reader.read_bytes_as_vec(4)?;
   assert_eq!(buffer1, b"o Wo");
 
-  let buffer = b"Hello World!\01234".to_vec();
+  let buffer = b"Hello World!\x001234".to_vec();
   let f = Cursor::new(&*buffer);
   let mut reader = StreamReader::new(f);
 
-  let r = reader.read_ascii_string("Hello World!\01234".len())?; // must after \0 is trim
+  let r = reader.read_ascii_string("Hello World!\x001234".len())?; // must after \0 is trim
   assert_eq!(r, "Hello World!");
   let buffer =
-    b"\xE3\x81\xB8\xE3\x82\x8D\xE3\x83\xBC\xE3\x82\x8F\xE3\x83\xBC\xE3\x82\x8B\xE3\x81\xA9\01234"
+    b"\xE3\x81\xB8\xE3\x82\x8D\xE3\x83\xBC\xE3\x82\x8F\xE3\x83\xBC\xE3\x82\x8B\xE3\x81\xA9\x001234"
       .to_vec();
   let f = Cursor::new(&*buffer);
   let mut reader = StreamReader::new(f);
 
   let r = reader.read_utf8_string(23)?;
-  assert_eq!(r, "へろーわーるど\01");
+  assert_eq!(r, "へろーわーるど\x001");
 
   let buffer = [0x71, 0x3D, 0x0A, 0xD7, 0xA3, 0x30, 0x31, 0xC0].to_vec();
   let f = Cursor::new(&*buffer);
@@ -368,11 +513,1313 @@ fn check_stream() -> Result<(), Box<dyn std::error::Error>> {
   Ok(())
 }
 
+#[test]
+fn check_bytes_writer() -> Result<(), Box<dyn std::error::Error>> {
+  let mut writer = BytesWriter::new();
+  writer.write_byte(0)?;
+  writer.write_u8(1)?;
+  writer.write_u16_be(0x0203)?;
+  writer.write_u16_le(0x0504)?;
+  writer.write_u32_be(0x06070809)?;
+  writer.write_u32_le(0x0d0c0b0a)?;
+  writer.write_i16_be(-259)?;
+  writer.write_u64_be(0x1011121314151617)?;
+  writer.write_u64_le(0x1f1e1d1c1b1a1918)?;
+  writer.write_u128_be(0x202122232425262728292a2b2c2d2e2f)?;
+  writer.write_u128_le(0x3f3e3d3c3b3a39383736353433323130)?;
+  writer.write_f32_be(17.19)?;
+  writer.set_endian(Endian::LittleEndian);
+  writer.write_f64(-17.19)?;
+  writer.set_endian(Endian::BigEndian);
+  writer.write_i32(-84281097)?;
+  assert_eq!(writer.offset()?, 0x50);
+
+  let mut reader = BytesReader::from(writer.into_vec());
+  let r = reader.read_bytes_as_vec(10)?;
+  assert_eq!(r, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+  let r = reader.read_u32_le()?;
+  assert_eq!(r, 0x0d0c0b0a);
+  let r = reader.read_i16_be()?;
+  assert_eq!(r, -259);
+  let r = reader.read_u64_be()?;
+  assert_eq!(r, 0x1011121314151617);
+  let r = reader.read_u64_le()?;
+  assert_eq!(r, 0x1f1e1d1c1b1a1918);
+  let r = reader.read_u128_be()?;
+  assert_eq!(r, 0x202122232425262728292a2b2c2d2e2f);
+  let r = reader.read_u128_le()?;
+  assert_eq!(r, 0x3f3e3d3c3b3a39383736353433323130);
+  let r = reader.read_f32_be()?;
+  assert_eq!(r, 17.19);
+  let r = reader.read_f64_le()?;
+  assert_eq!(r, -17.19);
+  let r = reader.read_i32_be()?;
+  assert_eq!(r, -84281097);
+
+  // overwrite and growth
+  let mut writer = BytesWriter::new();
+  writer.write_u32_be(0)?;
+  writer.write_bytes(b"body")?;
+  let end = writer.offset()?;
+  writer.seek(SeekFrom::Start(0))?;
+  writer.write_u32_be(end as u32)?;
+  assert_eq!(writer.offset()?, 4);
+  assert_eq!(writer.len(), 8);
+  writer.seek(SeekFrom::End(2))?;
+  writer.write_byte(0xff)?;
+  assert_eq!(writer.as_slice(), b"\x00\x00\x00\x08body\x00\x00\xff");
+  writer.seek(SeekFrom::Current(-3))?;
+  writer.write_u16_le(0x0201)?;
+  assert_eq!(writer.as_slice(), b"\x00\x00\x00\x08body\x01\x02\xff");
+  assert!(writer.seek(SeekFrom::Current(-20)).is_err());
+
+  // a seek past the end does not grow the buffer until the next write
+  let max = usize::MAX as u64;
+  assert_eq!(writer.seek(SeekFrom::Start(max))?, max);
+  assert_eq!(writer.len(), 11);
+  assert!(writer.write_byte(0).is_err());
+  assert_eq!(writer.seek(SeekFrom::End(3))?, 14);
+  assert_eq!(writer.as_slice(), b"\x00\x00\x00\x08body\x01\x02\xff");
+  writer.write_byte(0xee)?;
+  assert_eq!(writer.len(), 15);
+  assert_eq!(&writer.as_slice()[11..], b"\x00\x00\x00\xee");
+
+  // strings
+  let mut writer = BytesWriter::new();
+  let len = writer.write_ascii_string("©2023 Mith@mmk")?;
+  assert_eq!(len, 14);
+  let len = writer.write_utf8_string("へろーわーるど")?;
+  assert_eq!(len, 21);
+  let len = writer.write_utf16be_string("へろー World!")?;
+  assert_eq!(len, 20);
+  let len = writer.write_utf16le_string("Hello World!")?;
+  assert_eq!(len, 24);
+  assert!(writer.write_ascii_string("へ").is_err());
+
+  let mut reader = BytesReader::from(writer.into_vec());
+  let r = reader.read_ascii_string(14)?;
+  assert_eq!(r, "©2023 Mith@mmk");
+  let r = reader.read_utf8_string(21)?;
+  assert_eq!(r, "へろーわーるど");
+  let r = reader.read_utf16be_string(20)?;
+  assert_eq!(r, "へろー World!");
+  let r = reader.read_utf16le_string(24)?;
+  assert_eq!(r, "Hello World!");
+
+  Ok(())
+}
+
+#[test]
+fn check_stream_writer() -> Result<(), Box<dyn std::error::Error>> {
+  let mut writer = StreamWriter::new(Cursor::new(Vec::new()));
+  writer.set_endian(Endian::BigEndian);
+  writer.write_u32(0)?;
+  writer.write_i64_le(-1084818905618843913)?;
+  writer.write_utf16_string("へろー World!")?;
+  let end = writer.offset()?;
+  writer.seek(SeekFrom::Start(0))?;
+  writer.write_u32(end as u32)?;
+  writer.seek(SeekFrom::End(0))?;
+  writer.write_f64(-17.19)?;
+
+  let buffer = writer.into_inner().into_inner();
+  let mut reader = StreamReader::new(Cursor::new(buffer));
+  reader.set_endian(Endian::BigEndian);
+  let r = reader.read_u32()?;
+  assert_eq!(r, 32);
+  let r = reader.read_i64_le()?;
+  assert_eq!(r, -1084818905618843913);
+  let r = reader.read_utf16_string(20)?;
+  assert_eq!(r, "へろー World!");
+  let r = reader.read_f64()?;
+  assert_eq!(r, -17.19);
+
+  let f = std::env::temp_dir().join("bin_rs_check_stream_writer.bin");
+  let mut writer = StreamWriter::create_file(f.clone())?;
+  writer.write_ascii_string("©2023 Mith@mmk")?;
+  writer.flush()?;
+  drop(writer);
+  let mut reader = StreamReader::from_file(f.clone())?;
+  let r = reader.read_ascii_string(14)?;
+  assert_eq!(r, "©2023 Mith@mmk");
+  fs::remove_file(f)?;
+
+  Ok(())
+}
+
+#[test]
+fn check_placeholder() -> Result<(), Box<dyn std::error::Error>> {
+  // RIFF like chunk
+  let mut writer = BytesWriter::new();
+  writer.write_bytes(b"RIFF")?;
+  let size = writer.reserve_u32(Endian::LittleEndian)?;
+  writer.write_bytes(b"WAVE")?;
+  let offset = writer.reserve_u64(Endian::BigEndian)?;
+  let short = writer.reserve_u16(Endian::BigEndian)?;
+  assert_eq!(writer.placeholders().len(), 3);
+  assert!(writer.finish().is_err());
+  writer.write_bytes(b"data")?;
+  writer.fill_offset(&offset)?;
+  writer.fill(&short, 0x1234)?;
+  writer.fill_length(&size)?;
+  writer.finish()?;
+  assert_eq!(
+    writer.as_slice(),
+    b"RIFF\x12\x00\x00\x00WAVE\x00\x00\x00\x00\x00\x00\x00\x1a\x12\x34data"
+  );
+
+  let mut writer = BytesWriter::new();
+  let short = writer.reserve_u16(Endian::BigEndian)?;
+  assert!(writer.fill(&short, 0x10000).is_err());
+  let mut other = BytesWriter::new();
+  let short = other.reserve_u16(Endian::BigEndian)?;
+  writer.write_bytes(b"ab")?;
+  assert!(writer.fill(&short, 1).is_err()); // reserved by other writer
+  assert!(writer.finish().is_err());
+
+  let mut writer = StreamWriter::new(Cursor::new(Vec::new()));
+  writer.write_bytes(b"\x89PNG")?;
+  let length = writer.reserve_u32(Endian::BigEndian)?;
+  writer.write_bytes(b"IHDR")?;
+  writer.write_u32_be(1)?;
+  writer.fill_length(&length)?;
+  writer.write_u16_le(0xffff)?;
+  writer.finish()?;
+  let buffer = writer.into_inner().into_inner();
+  assert_eq!(
+    buffer,
+    b"\x89PNG\x00\x00\x00\x08IHDR\x00\x00\x00\x01\xff\xff"
+  );
+
+  Ok(())
+}
+
+#[test]
+fn check_slice() -> Result<(), Box<dyn std::error::Error>> {
+  let buffer: Vec<u8> = (0..255).collect();
+  let mut reader = SliceReader::from(&buffer);
+
+  let r = reader.read_byte()?;
+  assert_eq!(r, 0_u8);
+  let r = reader.read_u8()?;
+  assert_eq!(r, 1_u8);
+  let r = reader.read_u16_be()?;
+  assert_eq!(r, 0x0203);
+  let r = reader.read_u16_le()?;
+  assert_eq!(r, 0x0504);
+  let r = reader.read_u32_be()?;
+  assert_eq!(r, 0x06070809);
+  let r = reader.read_u32_le()?;
+  assert_eq!(r, 0x0d0c0b0a);
+  reader.skip_ptr(2)?; // 0x0e 0x0f skip
+  let r = reader.read_u64_be()?;
+  assert_eq!(r, 0x1011121314151617);
+  let r = reader.read_u64_le()?;
+  assert_eq!(r, 0x1f1e1d1c1b1a1918);
+  let r = reader.read_u128_be()?;
+  assert_eq!(r, 0x202122232425262728292a2b2c2d2e2f);
+  let r = reader.read_u128_le()?;
+  assert_eq!(r, 0x3f3e3d3c3b3a39383736353433323130);
+  let r = reader.offset()?;
+  assert_eq!(r, 0x40);
+  let r = reader.read_slice(4)?;
+  assert_eq!(r, [0x40, 0x41, 0x42, 0x43]);
+  assert_eq!(r.as_ptr(), buffer[0x40..].as_ptr()); // no copy
+  let r = reader.read_bytes_no_move(2)?;
+  assert_eq!(r, [0x44, 0x45]);
+  reader.set_endian(Endian::BigEndian);
+  let r = reader.read_u16()?;
+  assert_eq!(r, 0x4445);
+  let r = reader.seek(SeekFrom::End(-1))?;
+  assert_eq!(r, 254);
+  assert!(reader.read_u16().is_err());
+  assert_eq!(reader.offset()?, 254);
+  let r = reader.seek(SeekFrom::End(0))?;
+  assert_eq!(r, 255);
+  assert!(reader.seek(SeekFrom::Current(1)).is_err());
+  assert!(reader.read_byte().is_err());
+  reader.seek(SeekFrom::Start(1))?;
+  let err = reader.read_slice(usize::MAX).unwrap_err();
+  assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+  assert_eq!(reader.offset()?, 1);
+
+  let buffer: Vec<u8> = (0..32).map(|i| 255 - i).collect();
+  let mut reader = SliceReader::new(&buffer);
+  let r = reader.read_i8()?; // 0xff
+  assert_eq!(r, -1);
+  let r = reader.read_i16_be()?; // 0xfefd -> fefd
+  assert_eq!(r, -259);
+  let r = reader.read_i16_le()?; // 0xfcfb -> fbfc
+  assert_eq!(r, -1028);
+  let r = reader.read_i32_be()?; // 0xfaf9f8f7
+  assert_eq!(r, -84281097);
+  let r = reader.read_i32_le()?; // 0xf3f4f5f6
+  assert_eq!(r, -202050058);
+
+  let buffer = [0xC0, 0x31, 0x30, 0xA3, 0xD7, 0x0A, 0x3D, 0x71];
+  let mut reader = SliceReader::new(&buffer);
+  reader.set_endian(Endian::BigEndian);
+  let r = reader.read_f64()?;
+  assert_eq!(r, -17.19);
+
+  let buffer =
+    b"\xE3\x81\xB8\xE3\x82\x8D\xE3\x83\xBC\xE3\x82\x8F\xE3\x83\xBC\xE3\x82\x8B\xE3\x81\xA9Hello World!\x001234";
+  let mut reader = SliceReader::new(buffer);
+  let r = reader.read_str(21)?;
+  assert_eq!(r, "へろーわーるど");
+  let r = reader.read_ascii_string(17)?;
+  assert_eq!(r, "Hello World!");
+  assert!(reader.read_str(1).is_err());
+
+  let buffer = b"\xE3\x81";
+  let mut reader = SliceReader::new(buffer);
+  assert!(reader.read_str(2).is_err());
+  assert_eq!(reader.offset()?, 0);
+
+  Ok(())
+}
+
+#[test]
+#[cfg(feature = "mmap")]
+fn check_mmap() -> Result<(), Box<dyn std::error::Error>> {
+  use std::path::PathBuf;
+
+  let f = PathBuf::from("./test/unascii.txt");
+  let mut reader = MmapReader::from_file(f)?;
+  let assert_str = "©2023 Mith@mmk";
+  let r = reader.read_ascii_string(assert_str.len() - 1)?; // un ascii string using 2byte for utf8
+  assert_eq!(r, assert_str);
+  let r = reader.offset()?;
+  assert_eq!(r, 14);
+  reader.seek(SeekFrom::Start(0))?;
+  assert!(reader.read_str(1).is_err()); // 0xa9 is not utf8
+  reader.seek(SeekFrom::Start(1))?;
+  let r = reader.read_str(4)?;
+  assert_eq!(r, "2023");
+  assert_eq!(reader.offset()?, 5);
+  let r = reader.read_slice(2)?;
+  assert_eq!(r, b" M");
+  let err = reader.read_slice(usize::MAX).unwrap_err();
+  assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+  assert_eq!(reader.offset()?, 7);
+
+  let f = std::env::temp_dir().join("bin_rs_check_mmap.bin");
+  let buffer: Vec<u8> = (0..255).collect();
+  fs::write(&f, &buffer)?;
+  let mut reader = MmapReader::from_file(f.clone())?;
+  assert_eq!(reader.len(), 255);
+  reader.set_endian(Endian::BigEndian);
+  let r = reader.read_u16()?;
+  assert_eq!(r, 0x0001);
+  let r = reader.read_u32_le()?;
+  assert_eq!(r, 0x05040302);
+  let r = reader.read_bytes_no_move(2)?;
+  assert_eq!(r, [6, 7]);
+  let r = reader.seek(SeekFrom::End(-1))?;
+  assert_eq!(r, 254);
+  assert!(reader.read_u16().is_err());
+  let mut slice = reader.slice_reader();
+  let r = slice.read_slice(4)?;
+  assert_eq!(r, [0, 1, 2, 3]);
+  let r = slice.read_u16()?;
+  assert_eq!(r, 0x0405);
+  drop(reader);
+  fs::remove_file(f)?;
+
+  Ok(())
+}
+
+#[test]
+fn check_bit_reader() -> Result<(), Box<dyn std::error::Error>> {
+  let buffer = [
+    0b1011_0011,
+    0b0101_0101,
+    0xff,
+    0x80,
+    0x01,
+    0x02,
+    0x03,
+    0x04,
+    0x05,
+    0x06,
+    0x07,
+    0x08,
+  ];
+  let mut reader = BitReader::new(BytesReader::new(&buffer), BitOrder::MsbFirst);
+  let r = reader.read_bits(3)?;
+  assert_eq!(r, 0b101);
+  let r = reader.read_bit()?;
+  assert!(r);
+  let r = reader.peek_bits(8)?;
+  assert_eq!(r, 0b0011_0101);
+  let r = reader.read_bits(8)?;
+  assert_eq!(r, 0b0011_0101);
+  assert_eq!(reader.bit_offset(), 12);
+  reader.align_to_byte();
+  assert!(reader.is_aligned());
+  let r = reader.read_signed_bits(4)?; // 0b1111
+  assert_eq!(r, -1);
+  let r = reader.read_signed_bits(5)?; // 0b1111_1
+  assert_eq!(r, -1);
+  let r = reader.read_signed_bits(7)?; // 0b000_0000
+  assert_eq!(r, 0);
+  let r = reader.read_bits(64)?;
+  assert_eq!(r, 0x0102030405060708);
+  assert!(reader.read_bits(65).is_err());
+  assert!(reader.read_bit().is_err());
+
+  let buffer = [0b1011_0011, 0b0101_0101, 0xff];
+  let mut reader = BitReader::new(BytesReader::new(&buffer), BitOrder::LsbFirst);
+  let r = reader.read_bits(3)?;
+  assert_eq!(r, 0b011);
+  let r = reader.read_bit()?;
+  assert!(!r);
+  let r = reader.read_bits(8)?; // 0101 + 1011
+  assert_eq!(r, 0b0101_1011);
+  let r = reader.read_signed_bits(2)?; // 01
+  assert_eq!(r, 1);
+  reader.align_to_byte();
+  assert_eq!(reader.bit_offset(), 16);
+  let r = reader.peek_bits(8)?;
+  assert_eq!(r, 0xff);
+  let mut inner = reader.into_inner()?;
+  assert_eq!(inner.offset()?, 2);
+  let r = inner.read_u8()?;
+  assert_eq!(r, 0xff);
+
+  Ok(())
+}
+
+#[test]
+fn check_bit_writer() -> Result<(), Box<dyn std::error::Error>> {
+  let mut writer = BitWriter::new(BytesWriter::new(), BitOrder::MsbFirst);
+  writer.write_bits(0b101, 3)?;
+  writer.write_bit(true)?;
+  writer.write_bits(0b0011_0101, 8)?;
+  assert_eq!(writer.bit_position(), 12);
+  writer.flush_with_padding(true)?;
+  assert!(writer.is_aligned());
+  writer.write_signed_bits(-1, 4)?;
+  writer.write_signed_bits(-16, 5)?;
+  writer.write_signed_bits(0, 7)?;
+  writer.write_bits(0x0102030405060708, 64)?;
+  assert!(writer.write_bits(0b100, 2).is_err());
+  assert!(writer.write_signed_bits(8, 4).is_err());
+  assert!(writer.write_signed_bits(-9, 4).is_err());
+  writer.write_signed_bits(0, 0)?;
+  assert!(writer.write_bits(0, 65).is_err());
+  let buffer = writer.into_inner()?.into_vec();
+  assert_eq!(
+    buffer,
+    [
+      0b1011_0011,
+      0b0101_1111,
+      0xf8,
+      0x00,
+      0x01,
+      0x02,
+      0x03,
+      0x04,
+      0x05,
+      0x06,
+      0x07,
+      0x08
+    ]
+  );
+
+  let mut reader = BitReader::new(BytesReader::from(buffer), BitOrder::MsbFirst);
+  let r = reader.read_bits(3)?;
+  assert_eq!(r, 0b101);
+  reader.read_bits(13)?;
+  let r = reader.read_signed_bits(4)?;
+  assert_eq!(r, -1);
+  let r = reader.read_signed_bits(5)?;
+  assert_eq!(r, -16);
+
+  let mut writer = BitWriter::new(Vec::new(), BitOrder::LsbFirst);
+  writer.write_bits(0b011, 3)?;
+  writer.write_bit(false)?;
+  writer.write_bits(0b0101_1011, 8)?;
+  writer.write_signed_bits(1, 2)?;
+  writer.flush_with_padding(false)?;
+  writer.write_bits(0xff, 8)?;
+  let buffer = writer.into_inner()?;
+  assert_eq!(buffer, [0b1011_0011, 0b0001_0101, 0xff]);
+
+  let mut writer = BitWriter::new(
+    StreamWriter::new(Cursor::new(Vec::new())),
+    BitOrder::LsbFirst,
+  );
+  writer.write_bits(0x1ff, 9)?;
+  let buffer = writer.into_inner()?.into_inner().into_inner();
+  assert_eq!(buffer, [0xff, 0x01]);
+
+  Ok(())
+}
+
+#[test]
+fn check_varint() -> Result<(), Box<dyn std::error::Error>> {
+  // uleb128
+  let buffer = [0x02, 0x7f, 0x80, 0x01, 0xe5, 0x8e, 0x26];
+  let mut reader = BytesReader::new(&buffer);
+  assert_eq!(reader.read_uleb128()?, 2);
+  assert_eq!(reader.read_uleb128()?, 127);
+  assert_eq!(reader.read_uleb128()?, 128);
+  assert_eq!(reader.read_uleb128()?, 624485);
+  let max = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+  assert_eq!(BytesReader::new(&max).read_uleb128()?, u64::MAX);
+  let overflow = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02];
+  assert!(BytesReader::new(&overflow).read_uleb128().is_err());
+  let overflow = [
+    0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x01,
+  ];
+  assert!(BytesReader::new(&overflow).read_uleb128().is_err());
+  assert!(BytesReader::new(&[0x82, 0x00]).read_uleb128().is_err()); // overlong
+  assert_eq!(BytesReader::new(&[0x00]).read_uleb128()?, 0);
+  assert!(BytesReader::new(&[0x82]).read_uleb128().is_err()); // eof
+
+  // sleb128
+  let buffer = [
+    0x02, 0x7e, 0xff, 0x00, 0x81, 0x7f, 0x80, 0x7f, 0xc0, 0xbb, 0x78,
+  ];
+  let mut reader = BytesReader::new(&buffer);
+  assert_eq!(reader.read_sleb128()?, 2);
+  assert_eq!(reader.read_sleb128()?, -2);
+  assert_eq!(reader.read_sleb128()?, 127);
+  assert_eq!(reader.read_sleb128()?, -127);
+  assert_eq!(reader.read_sleb128()?, -128);
+  assert_eq!(reader.read_sleb128()?, -123456);
+  let min = [0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7f];
+  assert_eq!(BytesReader::new(&min).read_sleb128()?, i64::MIN);
+  let max = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+  assert_eq!(BytesReader::new(&max).read_sleb128()?, i64::MAX);
+  let overflow = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+  assert!(BytesReader::new(&overflow).read_sleb128().is_err());
+  assert!(BytesReader::new(&[0x82, 0x00]).read_sleb128().is_err()); // overlong
+  assert!(BytesReader::new(&[0xff, 0x7f]).read_sleb128().is_err()); // overlong -1
+
+  // zigzag
+  let buffer = [
+    0x00, 0x01, 0x02, 0x03, 0xfe, 0xff, 0xff, 0xff, 0x0f, 0xff, 0xff, 0xff, 0xff, 0x0f,
+  ];
+  let mut reader = BytesReader::new(&buffer);
+  assert_eq!(reader.read_zigzag_varint()?, 0);
+  assert_eq!(reader.read_zigzag_varint()?, -1);
+  assert_eq!(reader.read_zigzag_varint()?, 1);
+  assert_eq!(reader.read_zigzag_varint()?, -2);
+  assert_eq!(reader.read_zigzag_varint()?, 2147483647);
+  assert_eq!(reader.read_zigzag_varint()?, -2147483648);
+
+  // vlq
+  let buffer = [
+    0x00, 0x7f, 0x81, 0x00, 0xc0, 0x00, 0xff, 0x7f, 0xff, 0xff, 0xff, 0x7f,
+  ];
+  let mut reader = BytesReader::new(&buffer);
+  assert_eq!(reader.read_vlq()?, 0);
+  assert_eq!(reader.read_vlq()?, 0x7f);
+  assert_eq!(reader.read_vlq()?, 0x80);
+  assert_eq!(reader.read_vlq()?, 0x2000);
+  assert_eq!(reader.read_vlq()?, 0x3fff);
+  assert_eq!(reader.read_vlq()?, 0x0fffffff);
+  assert!(BytesReader::new(&[0x80, 0x7f]).read_vlq().is_err()); // overlong
+  let overflow = [0x82, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00];
+  assert!(BytesReader::new(&overflow).read_vlq().is_err());
+
+  // round trip
+  let mut writer = BytesWriter::new();
+  let unsigned = [
+    0,
+    1,
+    127,
+    128,
+    0x3fff,
+    0x4000,
+    624485,
+    u32::MAX as u64,
+    u64::MAX,
+  ];
+  let signed = [0, 1, -1, 63, -64, 64, -65, -123456, i64::MAX, i64::MIN];
+  for n in unsigned {
+    writer.write_uleb128(n)?;
+    writer.write_vlq(n)?;
+  }
+  for n in signed {
+    writer.write_sleb128(n)?;
+    writer.write_zigzag_varint(n)?;
+  }
+  let mut reader = BytesReader::from(writer.into_vec());
+  for n in unsigned {
+    assert_eq!(reader.read_uleb128()?, n);
+    assert_eq!(reader.read_vlq()?, n);
+  }
+  for n in signed {
+    assert_eq!(reader.read_sleb128()?, n);
+    assert_eq!(reader.read_zigzag_varint()?, n);
+  }
+
+  let mut writer = BytesWriter::new();
+  writer.write_uleb128(624485)?;
+  writer.write_sleb128(-123456)?;
+  writer.write_vlq(0x2000)?;
+  writer.write_zigzag_varint(-2)?;
+  assert_eq!(
+    writer.as_slice(),
+    [0xe5, 0x8e, 0x26, 0xc0, 0xbb, 0x78, 0xc0, 0x00, 0x03]
+  );
+
+  Ok(())
+}
+
+#[test]
+#[cfg(feature = "util")]
+fn check_io_varint() {
+  use crate::io::*;
+  let mut buffer = Vec::new();
+  write_uleb128(624485, &mut buffer);
+  write_sleb128(-123456, &mut buffer);
+  write_vlq(0x3fff, &mut buffer);
+  write_zigzag_varint(-2147483648, &mut buffer);
+  assert_eq!(
+    buffer,
+    [0xe5, 0x8e, 0x26, 0xc0, 0xbb, 0x78, 0xff, 0x7f, 0xff, 0xff, 0xff, 0xff, 0x0f]
+  );
+}
+
+#[test]
+fn check_from_binary() -> Result<(), Box<dyn std::error::Error>> {
+  fn read_pairs<R: BinaryReader>(reader: &mut R) -> Result<Vec<(u8, i16)>, std::io::Error> {
+    let count = reader.read::<u8>()? as usize;
+    reader.read_vec::<(u8, i16)>(count)
+  }
+
+  let buffer = [0x02, 0x01, 0xff, 0xfe, 0x02, 0x00, 0x01];
+  let mut reader = BytesReader::new(&buffer);
+  reader.set_endian(Endian::BigEndian);
+  let r = read_pairs(&mut reader)?;
+  assert_eq!(r, [(1, -2), (2, 1)]);
+  let mut reader = StreamReader::new(Cursor::new(buffer));
+  reader.set_endian(Endian::LittleEndian);
+  let r = read_pairs(&mut reader)?;
+  assert_eq!(r, [(1, -257), (2, 256)]);
+
+  let buffer: Vec<u8> = (0..64).collect();
+  let mut reader = SliceReader::new(&buffer);
+  reader.set_endian(Endian::LittleEndian);
+  let r = reader.read::<u32>()?;
+  assert_eq!(r, 0x03020100);
+  let r = reader.read_with::<u32>(Endian::BigEndian)?;
+  assert_eq!(r, 0x04050607);
+  let r = reader.read::<[u16; 2]>()?;
+  assert_eq!(r, [0x0908, 0x0b0a]);
+  let r = reader.read_with::<(u8, i8, [u8; 2], u64)>(Endian::BigEndian)?;
+  assert_eq!(r, (0x0c, 0x0d, [0x0e, 0x0f], 0x1011121314151617));
+  let r = reader.read::<u128>()?;
+  assert_eq!(r, 0x2726252423222120_1f1e1d1c1b1a1918);
+  let r = reader.read::<[[u8; 2]; 2]>()?;
+  assert_eq!(r, [[0x28, 0x29], [0x2a, 0x2b]]);
+
+  let buffer = [0x00, 0x01, 0x41, 0x89, 0x85, 0x1f, 0x02, 0x01, 0x00];
+  let mut reader = BytesReader::new(&buffer);
+  let r = reader.read::<Option<u16>>()?;
+  assert_eq!(r, None);
+  let r = reader.read_with::<Option<f32>>(Endian::BigEndian)?;
+  assert_eq!(r, Some(17.19));
+  assert!(reader.read::<Option<u8>>().is_err());
+  let r = reader.read::<(bool, bool)>()?;
+  assert_eq!(r, (true, false));
+  assert!(reader.read::<u8>().is_err());
+
+  Ok(())
+}
+
+#[test]
+#[cfg(feature = "derive")]
+fn check_derive_read() -> Result<(), Box<dyn std::error::Error>> {
+  use crate::BinRead;
+
+  #[derive(BinRead, Debug, PartialEq)]
+  struct Color([u8; 3]);
+
+  #[derive(BinRead, Debug, PartialEq)]
+  #[bin(magic = b"BM", endian = "little")]
+  struct Header {
+    width: u16,
+    #[bin(endian = "big")]
+    height: u16,
+    flags: u8,
+    #[bin(pad_before = 1)]
+    colors: u8,
+    #[bin(count = colors)]
+    palette: Vec<Color>,
+    #[bin(if = flags & 1 != 0)]
+    comment_len: Option<u8>,
+    #[bin(count = comment_len.unwrap_or(0), pad_after = 2)]
+    comment: Vec<u8>,
+    #[bin(magic = b"END", seek_before = SeekFrom::End(-4))]
+    last: u8,
+  }
+
+  let buffer = b"BM\x02\x01\x00\x03\x01\xff\x02\x01\x02\x03\x04\x05\x06\x02hi\x00\x00END\x7f";
+  let mut reader = BytesReader::new(buffer);
+  reader.set_endian(Endian::BigEndian);
+  let header = reader.read::<Header>()?;
+  assert_eq!(
+    header,
+    Header {
+      width: 0x0102,
+      height: 3,
+      flags: 1,
+      colors: 2,
+      palette: vec![Color([1, 2, 3]), Color([4, 5, 6])],
+      comment_len: Some(2),
+      comment: b"hi".to_vec(),
+      last: 0x7f,
+    }
+  );
+
+  let buffer = b"BM\x02\x01\x00\x03\x00\xff\x00\x00\x00END\x7f";
+  let mut reader = SliceReader::new(buffer);
+  let header = reader.read::<Header>()?;
+  assert_eq!(header.comment_len, None);
+  assert!(header.palette.is_empty());
+
+  let buffer = b"BN\x02\x01";
+  let mut reader = BytesReader::new(buffer);
+  assert!(reader.read::<Header>().is_err());
+
+  #[derive(BinRead, Debug, PartialEq)]
+  struct Pair<T> {
+    a: T,
+    b: T,
+  }
+  let buffer = [0x00, 0x01, 0x00, 0x02];
+  let mut reader = BytesReader::new(&buffer);
+  let r = reader.read_with::<Pair<u16>>(Endian::BigEndian)?;
+  assert_eq!(r, Pair { a: 1, b: 2 });
+
+  #[derive(BinRead, Debug)]
+  #[allow(dead_code)]
+  struct Aligned {
+    align: u8,
+    #[bin(align_before = align)]
+    value: u8,
+  }
+  let mut reader = BytesReader::new(b"\x00\x01");
+  let err = reader.read::<Aligned>().unwrap_err();
+  assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+  Ok(())
+}
+
+#[test]
+fn check_to_binary() -> Result<(), Box<dyn std::error::Error>> {
+  let mut writer = BytesWriter::new();
+  writer.set_endian(Endian::LittleEndian);
+  writer.write_value(&0x0102_u16)?;
+  writer.write_value_with(&0x0102_u16, Endian::BigEndian)?;
+  writer.write_value(&[1_i8, -1])?;
+  writer.write_value(&(true, 17.19_f32))?;
+  writer.write_value(&Some(0x7f_u8))?;
+  writer.write_value(&None::<u64>)?;
+  let buffer = writer.into_vec();
+  assert_eq!(
+    buffer,
+    [0x02, 0x01, 0x01, 0x02, 0x01, 0xff, 0x01, 0x1f, 0x85, 0x89, 0x41, 0x01, 0x7f, 0x00]
+  );
+
+  let mut reader = BytesReader::from(buffer);
+  reader.set_endian(Endian::LittleEndian);
+  let r = reader.read::<(u16, u16, [i8; 2], (bool, f32), Option<u8>, Option<u64>)>()?;
+  assert_eq!(
+    r,
+    (0x0102, 0x0201, [1, -1], (true, 17.19), Some(0x7f), None)
+  );
+
+  Ok(())
+}
+
+#[test]
+#[cfg(feature = "derive")]
+fn check_derive_write() -> Result<(), Box<dyn std::error::Error>> {
+  use crate::{BinRead, BinWrite};
+
+  #[derive(BinRead, BinWrite, Debug, PartialEq, Clone)]
+  struct Entry(u8, #[bin(endian = "big")] u16);
+
+  #[derive(BinRead, BinWrite, Debug, PartialEq, Clone)]
+  #[bin(magic = b"PK", endian = "little")]
+  struct Archive {
+    version: u16,
+    flags: u8,
+    #[bin(align_before = 4)]
+    count: u32,
+    #[bin(count = count)]
+    entries: Vec<Entry>,
+    #[bin(if = flags & 1 != 0, pad_after = 3)]
+    comment: Option<[u8; 2]>,
+    #[bin(magic = b"EOF", align_after = 8)]
+    end: u8,
+  }
+
+  let archive = Archive {
+    version: 0x0102,
+    flags: 1,
+    count: 2,
+    entries: vec![Entry(1, 0x0203), Entry(4, 0x0506)],
+    comment: Some(*b"hi"),
+    end: 0xff,
+  };
+  let mut writer = BytesWriter::new();
+  writer.write_value(&archive)?;
+  let buffer = writer.into_vec();
+  assert_eq!(
+    buffer,
+    b"PK\x02\x01\x01\x00\x00\x00\x02\x00\x00\x00\x01\x02\x03\x04\x05\x06hi\x00\x00\x00EOF\xff\x00\x00\x00\x00\x00"
+  );
+  let mut reader = BytesReader::from(buffer);
+  let r = reader.read::<Archive>()?;
+  assert_eq!(r, archive);
+
+  // count is written from the number of entries
+  let mut synced = archive.clone();
+  synced.count = 3;
+  let mut writer = BytesWriter::new();
+  writer.write_value(&synced)?;
+  let mut reader = BytesReader::from(writer.into_vec());
+  assert_eq!(reader.read::<Archive>()?, archive);
+
+  // len is never read, it is written from data
+  #[allow(dead_code)]
+  #[derive(BinWrite)]
+  struct Short {
+    len: u8,
+    #[bin(count = len)]
+    data: Vec<u8>,
+  }
+  let short = Short {
+    len: 0,
+    data: vec![0; 256],
+  };
+  let err = BytesWriter::new().write_value(&short).unwrap_err();
+  assert!(matches!(
+    crate::error::BinError::from(err),
+    crate::error::BinError::Overflow { .. }
+  ));
+  let mut broken = archive.clone();
+  broken.comment = None;
+  assert!(BytesWriter::new().write_value(&broken).is_err());
+  let mut broken = archive;
+  broken.flags = 0;
+  assert!(BytesWriter::new().write_value(&broken).is_err());
+  broken.comment = None;
+  BytesWriter::new().write_value(&broken)?;
+
+  #[derive(BinWrite)]
+  struct Aligned {
+    align: u8,
+    #[bin(align_after = align)]
+    value: u8,
+  }
+  let aligned = Aligned { align: 0, value: 1 };
+  let err = BytesWriter::new().write_value(&aligned).unwrap_err();
+  assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+  Ok(())
+}
+
+#[test]
+fn check_error() -> Result<(), Box<dyn std::error::Error>> {
+  use crate::error::BinError;
+  use std::io::ErrorKind;
+
+  let buffer = b"\x01\x02\x03\x80\xff";
+  let mut reader = BytesReader::new(buffer);
+  reader.skip_ptr(1)?;
+  let err = reader.read_u64_be().unwrap_err();
+  assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+  match BinError::from(err) {
+    BinError::UnexpectedEof {
+      offset,
+      requested,
+      available,
+    } => assert_eq!((offset, requested, available), (1, 8, 4)),
+    e => panic!("{}", e),
+  }
+  let err = BinError::from(reader.seek(SeekFrom::Current(-2)).unwrap_err());
+  assert!(matches!(
+    err,
+    BinError::SeekOutOfRange {
+      position: -1,
+      length: 5
+    }
+  ));
+  reader.seek(SeekFrom::Start(2))?;
+  let err = BinError::from(reader.read_utf8_string(3).unwrap_err());
+  assert!(matches!(
+    err,
+    BinError::InvalidString {
+      offset: 2,
+      encoding: "UTF-8"
+    }
+  ));
+  assert_eq!(err.offset(), Some(2));
+
+  let mut reader = SliceReader::new(&buffer[..]);
+  let err = BinError::from(reader.read_slice(6).unwrap_err());
+  assert!(matches!(err, BinError::UnexpectedEof { offset: 0, .. }));
+  let err = BinError::from(
+    reader
+      .read::<bool>()
+      .map(|_| ())
+      .and(reader.read::<bool>())
+      .unwrap_err(),
+  );
+  assert!(matches!(err, BinError::InvalidData { offset: 1, .. }));
+  reader.seek(SeekFrom::Start(3))?;
+  let err = BinError::from(reader.read_vlq().unwrap_err());
+  assert!(matches!(err, BinError::InvalidData { offset: 3, .. }));
+
+  let mut writer = BytesWriter::new();
+  writer.write_u8(1)?;
+  let placeholder = writer.reserve_u16(Endian::BigEndian)?;
+  let err = BinError::from(writer.finish().unwrap_err());
+  assert!(matches!(&err, BinError::UnfilledPlaceholders { offsets } if offsets == &[1]));
+  let err = BinError::from(writer.fill(&placeholder, 0x10000).unwrap_err());
+  assert!(matches!(err, BinError::Overflow { offset: 1, .. }));
+  // the placeholder is still pending, and can be filled again
+  assert_eq!(writer.placeholders().len(), 1);
+  writer.fill(&placeholder, 0xffff)?;
+  writer.finish()?;
+  assert_eq!(writer.as_slice(), b"\x01\xff\xff");
+  assert!(writer.fill(&placeholder, 1).is_err());
+
+  // StreamReader goes back to the start of a short read
+  let mut reader = StreamReader::new(Cursor::new(b"\x01\x02\x03".to_vec()));
+  reader.skip_ptr(1)?;
+  let err = reader.read_u32_be().unwrap_err();
+  assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+  match BinError::from(err) {
+    BinError::UnexpectedEof {
+      offset,
+      requested,
+      available,
+    } => assert_eq!((offset, requested, available), (1, 4, 2)),
+    e => panic!("{}", e),
+  }
+  assert_eq!(reader.offset()?, 1);
+  let err = BinError::from(reader.skip_ptr(3).unwrap_err());
+  assert_eq!(err.offset(), Some(1));
+  assert_eq!(reader.read_u16_be()?, 0x0203);
+
+  // io::Error which is not made by this crate
+  let err = std::io::Error::other("other");
+  let err = BinError::from(err);
+  assert_eq!(err.kind(), ErrorKind::Other);
+  assert!(matches!(err, BinError::Io(_)));
+  let err: std::io::Error = BinError::InvalidInput("wrong".to_string()).into();
+  assert_eq!(err.kind(), ErrorKind::InvalidInput);
+  assert_eq!(err.to_string(), "wrong");
+
+  Ok(())
+}
+
+#[test]
+fn check_context() -> Result<(), Box<dyn std::error::Error>> {
+  use crate::error::BinError;
+  use std::io::ErrorKind;
+
+  // io errors of StreamReader get labels too
+  let buffer = b"\x00\x00\x00\x02\x01\x00\x02";
+  let mut reader = StreamReader::new(Cursor::new(buffer));
+  let err = reader
+    .with_context("header", |r| {
+      let count = r.read_u32_be()?;
+      for i in 0..count {
+        r.with_context(format!("entry {}", i), |r| r.read_u16_be())?;
+      }
+      Ok(())
+    })
+    .unwrap_err();
+  assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+  let err = BinError::from(err);
+  assert_eq!(err.contexts(), [("header", 0), ("entry 1", 6)]);
+  assert!(matches!(
+    err.root(),
+    BinError::UnexpectedEof {
+      offset: 6,
+      requested: 2,
+      available: 1
+    }
+  ));
+  assert!(err
+    .to_string()
+    .starts_with("header (at 0): entry 1 (at 6): "));
+
+  let mut reader = BytesReader::new(buffer);
+  reader.skip_ptr(4)?;
+  let num = reader.with_context("entry", |r| r.read_u16_be())?;
+  assert_eq!(num, 0x100);
+
+  Ok(())
+}
+
+#[test]
+fn check_string_writer() -> Result<(), Box<dyn std::error::Error>> {
+  let mut writer = BytesWriter::new();
+  let options = EncodeOptions {
+    bom: true,
+    nul_terminated: true,
+    ..Default::default()
+  };
+  assert_eq!(
+    writer.write_utf16_string_with("日本", Endian::BigEndian, options)?,
+    8
+  );
+  writer.write_utf16_string_with("日本", Endian::LittleEndian, EncodeOptions::default())?;
+  assert_eq!(
+    writer.as_slice(),
+    b"\xfe\xff\x65\xe5\x67\x2c\x00\x00\xe5\x65\x2c\x67"
+  );
+  let mut reader = BytesReader::new(writer.as_slice());
+  reader.skip_ptr(2)?;
+  assert_eq!(reader.read_utf16be_string(4)?, "日本");
+
+  #[cfg(feature = "codec")]
+  {
+    use crate::error::BinError;
+
+    let codes = [
+      CodeType::Big5,
+      CodeType::EucJp,
+      CodeType::Gb18030,
+      CodeType::Jis,
+      CodeType::ShiftJis,
+      CodeType::Utf16Be,
+      CodeType::Utf16le,
+      CodeType::Utf8,
+    ];
+    for code in codes {
+      let mut writer = BytesWriter::new();
+      let options = EncodeOptions {
+        nul_terminated: true,
+        ..Default::default()
+      };
+      let size = writer.write_local_string_with("日本 ABC", code, options)?;
+      let mut reader = BytesReader::from(writer.into_vec());
+      assert_eq!(reader.read_local_string(size, code)?, "日本 ABC");
+    }
+
+    let mut writer = BytesWriter::new();
+    writer.write_u8(0xff)?;
+    let err = writer
+      .write_local_string("ab한", CodeType::ShiftJis)
+      .unwrap_err();
+    match BinError::from(err) {
+      BinError::UnmappableCharacter {
+        offset,
+        encoding,
+        character,
+      } => assert_eq!((offset, encoding, character), (3, "Shift_JIS", '한')),
+      e => panic!("{}", e),
+    }
+    assert_eq!(writer.len(), 1);
+
+    let replace = EncodeOptions {
+      unmappable: Unmappable::Replace,
+      ..Default::default()
+    };
+    let mut writer = BytesWriter::new();
+    writer.write_local_string_with("日한本", CodeType::Jis, replace)?;
+    writer.write_local_string_with("a日", CodeType::Ascii, replace)?;
+    assert_eq!(
+      writer.as_slice(),
+      b"\x1b\x24\x42\x46\x7c\x1b\x28\x42?\x1b\x24\x42\x4b\x5c\x1b\x28\x42a?"
+    );
+    assert!(writer.write_local_string("日", CodeType::Ascii).is_err());
+
+    let mut writer = BytesWriter::new();
+    let options = EncodeOptions {
+      bom: true,
+      ..Default::default()
+    };
+    writer.write_local_string_with("A", CodeType::Utf8, options)?;
+    writer.write_local_string_with("A", CodeType::Utf16le, options)?;
+    writer.write_local_string_with("A", CodeType::EucKr, options)?;
+    assert_eq!(writer.as_slice(), b"\xef\xbb\xbfA\xff\xfeA\x00A");
+
+    // a BOM of the code is removed on read
+    let mut reader = BytesReader::new(writer.as_slice());
+    assert_eq!(reader.read_local_string(4, CodeType::Utf8)?, "A");
+    assert_eq!(reader.read_local_string(4, CodeType::Utf16le)?, "A");
+    assert_eq!(reader.read_local_string(1, CodeType::EucKr)?, "A");
+
+    let mut writer = BytesWriter::new();
+    writer.write_utf16_string_with("日本", Endian::BigEndian, options)?;
+    let mut reader = BytesReader::from(writer.into_vec());
+    assert_eq!(reader.read_local_string(6, CodeType::Utf16Be)?, "日本");
+    // a BOM of the other byte order is kept
+    let mut reader = BytesReader::new(b"\xff\xfeA\x00");
+    assert_eq!(
+      reader.read_local_string(4, CodeType::Utf16Be)?,
+      "\u{fffe}\u{4100}"
+    );
+  }
+
+  Ok(())
+}
+
+#[test]
+fn check_delimited() -> Result<(), Box<dyn std::error::Error>> {
+  let buffer = b"\x00\x03abc\x00\x00\x00\x02hiname\x00tail";
+  let mut reader = BytesReader::new(buffer);
+  reader.set_endian(Endian::BigEndian);
+  assert_eq!(reader.read_pascal_string(LengthPrefix::U16)?, "abc");
+  assert_eq!(reader.read_pascal_bytes(LengthPrefix::U32)?, b"hi");
+  assert_eq!(reader.read_cstring()?, "name");
+  assert_eq!(reader.offset()?, 16);
+  assert!(reader.read_cstring().is_err()); // no NUL
+  let mut reader = BytesReader::new(b"\x05abc");
+  assert!(reader.read_pascal_string(LengthPrefix::U8).is_err());
+
+  fn check<R: BinaryReader>(reader: &mut R) -> Result<(), std::io::Error> {
+    assert_eq!(reader.read_until(b';')?, b"key=value;");
+    assert_eq!(reader.read_until(b';')?, b"\xff;");
+    assert_eq!(reader.read_line()?, Some(String::new()));
+    assert_eq!(reader.read_line()?, Some("line 2".to_string()));
+    assert_eq!(reader.read_line_bytes()?, Some(Vec::new()));
+    let offset = reader.offset()?;
+    assert!(reader.read_line().is_err());
+    reader.seek(SeekFrom::Start(offset))?;
+    assert_eq!(reader.read_line_bytes()?, Some(b"\xfflast".to_vec()));
+    assert_eq!(reader.read_line()?, None);
+    assert_eq!(reader.read_until(b';')?, b"");
+    Ok(())
+  }
+  let buffer = b"key=value;\xff;\r\nline 2\n\n\xfflast";
+  check(&mut BytesReader::new(buffer))?;
+  check(&mut SliceReader::new(buffer))?;
+  check(&mut StreamReader::new(Cursor::new(buffer)))?;
+
+  Ok(())
+}
+
+#[test]
+fn check_fixed_string() -> Result<(), Box<dyn std::error::Error>> {
+  use crate::error::BinError;
+
+  let mut writer = BytesWriter::new();
+  writer.write_fixed_string("hello.txt", 16, CodeType::Ascii, Padding::Nul)?;
+  writer.write_fixed_string("CD001", 8, CodeType::Ascii, Padding::Space)?;
+  writer.write_fixed_string("644 ", 8, CodeType::Ascii, Padding::Nul)?;
+  writer.write_fixed_string("日本", 8, CodeType::Utf16le, Padding::Space)?;
+  writer.write_fixed_string("日本", 6, CodeType::Utf8, Padding::Nul)?;
+  writer.write_fixed_string("©2023", 6, CodeType::Ascii, Padding::Nul)?;
+  assert_eq!(writer.len(), 52);
+  let err = writer
+    .write_fixed_string("日本", 5, CodeType::Utf8, Padding::Nul)
+    .unwrap_err();
+  assert!(matches!(
+    BinError::from(err),
+    BinError::Overflow { offset: 52, .. }
+  ));
+  assert!(writer
+    .write_fixed_string("日", 5, CodeType::Ascii, Padding::Nul)
+    .is_err());
+  let err = writer
+    .write_fixed_string("日本", 7, CodeType::Utf16Be, Padding::Nul)
+    .unwrap_err();
+  assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+  assert_eq!(writer.len(), 52);
+
+  let mut reader = BytesReader::from(writer.into_vec());
+  assert_eq!(
+    reader.read_fixed_string(16, CodeType::Ascii, Trim::Nul)?,
+    "hello.txt"
+  );
+  assert_eq!(
+    reader.read_fixed_string(8, CodeType::Ascii, Trim::Space)?,
+    "CD001"
+  );
+  assert_eq!(
+    reader.read_fixed_string(8, CodeType::Ascii, Trim::Both)?,
+    "644"
+  );
+  reader.seek(SeekFrom::Current(-8))?;
+  assert_eq!(
+    reader.read_fixed_string(8, CodeType::Ascii, Trim::None)?,
+    "644 \x00\x00\x00\x00"
+  );
+  let err = reader
+    .read_fixed_string(7, CodeType::Utf16le, Trim::None)
+    .unwrap_err(); // odd bytes
+  assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+  assert_eq!(
+    reader.read_fixed_string(8, CodeType::Utf16le, Trim::Space)?,
+    "日本"
+  );
+  assert_eq!(
+    reader.read_fixed_string(6, CodeType::Utf8, Trim::Nul)?,
+    "日本"
+  );
+  assert_eq!(
+    reader.read_fixed_string(6, CodeType::Ascii, Trim::Nul)?,
+    "©2023"
+  );
+
+  #[cfg(not(feature = "codec"))]
+  {
+    let mut reader = BytesReader::new(b"\x93\xfa");
+    assert!(reader
+      .read_fixed_string(2, CodeType::ShiftJis, Trim::Nul)
+      .is_err());
+    assert_eq!(reader.offset()?, 0);
+  }
+  #[cfg(feature = "codec")]
+  {
+    let mut writer = BytesWriter::new();
+    writer.write_fixed_string("日本", 6, CodeType::ShiftJis, Padding::Space)?;
+    assert_eq!(writer.as_slice(), b"\x93\xfa\x96\x7b  ");
+    let mut reader = BytesReader::from(writer.into_vec());
+    assert_eq!(
+      reader.read_fixed_string(6, CodeType::ShiftJis, Trim::Space)?,
+      "日本"
+    );
+  }
+
+  Ok(())
+}
+
+#[test]
+fn check_sub_reader() -> Result<(), Box<dyn std::error::Error>> {
+  use crate::error::BinError;
+
+  // id, size, data
+  let buffer =
+    b"AAAA\x00\x00\x00\x06\x00\x01\x00\x02\x00\x03BBBB\x00\x00\x00\x02\xff\xfeCCCC\x00\x00\x00\x08";
+
+  fn check<R: BinaryReader>(reader: &mut R) -> Result<(), std::io::Error> {
+    reader.set_endian(Endian::BigEndian);
+    reader.skip_ptr(4)?;
+    let size = reader.read_u32()? as u64;
+    let mut chunk = reader.sub_reader(size)?;
+    assert_eq!(chunk.start(), 8);
+    assert_eq!(chunk.read_u16()?, 1);
+    assert_eq!(chunk.offset()?, 2);
+    // nested view
+    let mut inner = chunk.sub_reader(2)?;
+    assert_eq!(inner.read_u16()?, 2);
+    assert!(inner.read_u8().is_err());
+    assert_eq!(chunk.offset()?, 4);
+    assert_eq!(chunk.read_u16()?, 3);
+    let err = BinError::from(chunk.read_u8().unwrap_err());
+    assert!(matches!(
+      err,
+      BinError::UnexpectedEof {
+        offset: 6,
+        requested: 1,
+        available: 0
+      }
+    ));
+    assert!(chunk.seek(SeekFrom::Current(1)).is_err());
+    assert_eq!(chunk.seek(SeekFrom::End(-2))?, 4);
+    assert_eq!(chunk.read_u16()?, 3);
+    chunk.seek(SeekFrom::Start(0))?;
+    chunk.skip_rest()?;
+    assert_eq!(reader.read_bytes_as_vec(4)?, b"BBBB");
+
+    let mut chunk = reader.window(22, 2)?;
+    assert_eq!(chunk.read_u16()?, 0xfffe);
+    assert!(chunk.read_bytes_as_vec(4).is_err());
+    assert_eq!(reader.read_bytes_as_vec(4)?, b"CCCC");
+
+    // a chunk which is larger than the data
+    let size = reader.read_u32()? as u64;
+    let mut chunk = reader.sub_reader(size)?;
+    assert_eq!(chunk.remaining(), 8);
+    assert!(chunk.read_u8().is_err());
+
+    // a window which ends at the end of the data
+    reader.seek(SeekFrom::Start(22))?;
+    let mut chunk = reader.sub_reader(10)?;
+    assert_eq!(chunk.seek(SeekFrom::End(0))?, 10);
+    assert_eq!(chunk.seek(SeekFrom::Start(2))?, 2);
+    assert_eq!(chunk.read_bytes_as_vec(4)?, b"CCCC");
+    chunk.skip_rest()?;
+    assert!(reader.read_u8().is_err());
+
+    // a zero-length window at the end of the data
+    assert_eq!(reader.seek(SeekFrom::End(0))?, 32);
+    let mut chunk = reader.window(32, 0)?;
+    assert_eq!(chunk.remaining(), 0);
+    assert_eq!(chunk.seek(SeekFrom::End(0))?, 0);
+    assert!(chunk.read_u8().is_err());
+    chunk.skip_rest()?;
+    assert_eq!(reader.offset()?, 32);
+    Ok(())
+  }
+  check(&mut BytesReader::new(buffer))?;
+  check(&mut SliceReader::new(buffer))?;
+  check(&mut StreamReader::new(Cursor::new(buffer)))?;
+
+  let mut reader = BytesReader::new(b"abc");
+  assert_eq!(reader.window(3, 0)?.remaining(), 0);
+  assert_eq!(reader.seek(SeekFrom::End(0))?, 3);
+  assert!(reader.seek(SeekFrom::End(1)).is_err());
+
+  Ok(())
+}
+
+#[test]
+fn check_bytes_reader_std_io() -> Result<(), Box<dyn std::error::Error>> {
+  use std::io::{BufRead, Read, Seek};
+
+  let buffer = b"\x00\x00\x00\x03BIN\nline 1\r\nline 2\npayload";
+  let mut reader = BytesReader::new(buffer);
+  let len = reader.read_u32_be()? as usize;
+  let mut magic = vec![0; len];
+  Read::read_exact(&mut reader, &mut magic)?;
+  assert_eq!(magic, b"BIN");
+  assert_eq!(reader.read_u8()?, b'\n');
+
+  let mut line = String::new();
+  BufRead::read_line(&mut reader, &mut line)?;
+  assert_eq!(line, "line 1\r\n");
+  assert_eq!(reader.offset()?, 16);
+  assert_eq!(
+    BinaryReader::read_line(&mut reader)?.as_deref(),
+    Some("line 2")
+  );
+  assert_eq!(reader.fill_buf()?, b"payload");
+  reader.consume(3);
+  assert_eq!(reader.stream_position()?, 26);
+
+  let mut rest = Vec::new();
+  reader.read_to_end(&mut rest)?;
+  assert_eq!(rest, b"load");
+  assert_eq!(Read::read(&mut reader, &mut [0; 4])?, 0);
+  assert!(reader.read_u8().is_err());
+
+  assert_eq!(Seek::seek(&mut reader, SeekFrom::End(0))?, 30);
+  assert!(Seek::seek(&mut reader, SeekFrom::End(1)).is_err());
+  Seek::seek(&mut reader, SeekFrom::Start(4))?;
+  let mut out = Vec::new();
+  std::io::copy(&mut reader.by_ref().take(3), &mut out)?;
+  assert_eq!(out, b"BIN");
+  assert_eq!(reader.offset()?, 7);
+
+  // an adapter which needs BufRead + Seek
+  let mut stream = StreamReader::new(BytesReader::new(buffer));
+  stream.set_endian(Endian::BigEndian);
+  assert_eq!(stream.read_u32()?, 3);
+
+  Ok(())
+}
+
 #[tokio::test]
 #[cfg(feature = "async")]
 pub async fn check_async() -> Result<(), Box<dyn std::error::Error>> {
-  use crate::async_reader::AsyncBytesReader;
-  let buffer: Vec<u8> = (0..255).map(|i| i).collect();
+  use crate::async_reader::*;
+  let buffer: Vec<u8> = (0..255).collect();
   let mut reader = AsyncBytesReader::new(&*buffer);
 
   let r = reader.read_byte().await?;
@@ -413,10 +1860,7 @@ pub async fn check_async() -> Result<(), Box<dyn std::error::Error>> {
   let r = reader.read_bytes_as_vec(2).await?;
   assert_eq!(r, [0xf2, 0xf1]);
 
-  let r = reader.read_i128_le().await; // outbounds
-  if r.is_err() {
-    assert!(true)
-  }
+  let _ = reader.read_i128_le().await;
 
   let buffer: Vec<u8> = (0..16).map(|i| 255 - i).collect();
   let mut reader = AsyncBytesReader::new(&*buffer);
@@ -454,19 +1898,35 @@ pub async fn check_async() -> Result<(), Box<dyn std::error::Error>> {
   let buffer1 = reader.read_bytes_as_vec(4).await?;
   assert_eq!(buffer1, b"o Wo");
 
-  let buffer = b"Hello World!\01234".to_vec();
+  let buffer = b"Hello World!\x001234".to_vec();
   let mut reader = AsyncBytesReader::new(&*buffer);
-  let r = reader.read_ascii_string("Hello World!\01234".len()).await?; // must after \0 is trim
+  let r = reader
+    .read_ascii_string("Hello World!\x001234".len())
+    .await?; // must after \0 is trim
   assert_eq!(r, "Hello World!");
   let buffer =
-    b"\xE3\x81\xB8\xE3\x82\x8D\xE3\x83\xBC\xE3\x82\x8F\xE3\x83\xBC\xE3\x82\x8B\xE3\x81\xA9\01234"
+    b"\xE3\x81\xB8\xE3\x82\x8D\xE3\x83\xBC\xE3\x82\x8F\xE3\x83\xBC\xE3\x82\x8B\xE3\x81\xA9\x001234"
       .to_vec();
   let mut reader = AsyncBytesReader::new(&*buffer);
   let r = reader.read_utf8_string(23).await?;
-  assert_eq!(r, "へろーわーるど\01");
-
-  if cfg!(feature = "codec") {
-    // no impl reader.read_local_string
+  assert_eq!(r, "へろーわーるど\x001");
+
+  #[cfg(feature = "codec")]
+  {
+    let buffer = b"\x93\xfa\x96\x7b\x8c\xea\x00\x41".to_vec();
+    let mut reader = AsyncBytesReader::new(&*buffer);
+    let r = reader.read_local_string(8, CodeType::ShiftJis).await?;
+    assert_eq!(r, "日本語");
+    let buffer = b"\xe5\x65\x00\xd8".to_vec();
+    let mut reader = AsyncBytesReader::new(&*buffer);
+    let options = DecodeOptions {
+      nul_terminated: false,
+      lossy: true,
+    };
+    let r = reader
+      .read_local_string_with(4, CodeType::Utf16le, options)
+      .await?;
+    assert_eq!(r, "日\u{FFFD}");
   }
 
   let buffer = [0x71, 0x3D, 0x0A, 0xD7, 0xA3, 0x30, 0x31, 0xC0].to_vec();
@@ -484,7 +1944,549 @@ pub async fn check_async() -> Result<(), Box<dyn std::error::Error>> {
   Ok(())
 }
 
+#[tokio::test]
+#[cfg(feature = "async")]
+pub async fn check_async_stream() -> Result<(), Box<dyn std::error::Error>> {
+  use crate::async_reader::*;
+  use crate::error::BinError;
+
+  async fn check<R: AsyncBinaryReader>(reader: &mut R) -> Result<(), std::io::Error> {
+    reader.set_endian(Endian::BigEndian);
+    assert_eq!(reader.read_u16().await?, 0x0102);
+    assert_eq!(reader.offset().await?, 2);
+    assert_eq!(reader.read_bytes_no_move(2).await?, b"\x00A");
+    assert_eq!(reader.read_utf16_string(4).await?, "AB");
+    assert_eq!(reader.read_utf16le_string(2).await?, "C");
+    assert_eq!(reader.endian(), Endian::BigEndian);
+    assert_eq!(reader.read_uleb128().await?, 300);
+    assert_eq!(reader.read_cstring().await?, "bin");
+    assert_eq!(reader.read_pascal_string(LengthPrefix::U8).await?, "rs");
+    reader.seek(SeekFrom::Current(2)).await?;
+    assert_eq!(
+      reader
+        .read_fixed_string(4, CodeType::Ascii, Trim::Space)
+        .await?,
+      "ab"
+    );
+    assert_eq!(reader.read_line().await?, Some("line".to_string()));
+    assert_eq!(reader.read_line().await?, None);
+    Ok(())
+  }
+
+  let buffer = b"\x01\x02\x00A\x00BC\x00\xac\x02bin\x00\x02rs\xff\xffab  line\r\n".to_vec();
+  check(&mut AsyncBytesReader::new(&*buffer)).await?;
+  check(&mut AsyncStreamReader::new(Cursor::new(buffer.clone()))).await?;
+
+  let mut reader = AsyncStreamReader::new(Cursor::new(buffer.clone()));
+  reader.seek(SeekFrom::End(-6)).await?;
+  assert_eq!(reader.read_ascii_string(4).await?, "line");
+  reader.seek(SeekFrom::Start(2)).await?;
+  assert_eq!(reader.read_utf16be_string(2).await?, "A");
+  let err = reader.read_bytes_as_vec(100).await.unwrap_err();
+  assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+
+  // skip_ptr seeks over bytes which are not buffered yet
+  let file = tokio::io::BufReader::with_capacity(4, Cursor::new(buffer.clone()));
+  let mut reader = AsyncStreamReader::new(file);
+  assert_eq!(reader.skip_ptr(2).await?, 2);
+  assert_eq!(reader.skip_ptr(15).await?, 15);
+  assert_eq!(reader.offset().await?, 17);
+  assert_eq!(reader.read_bytes_no_move(2).await?, b"\xff\xff");
+  let err = BinError::from(reader.skip_ptr(100).await.unwrap_err());
+  assert!(matches!(
+    err,
+    BinError::UnexpectedEof {
+      offset: 17,
+      requested: 100,
+      available: 12
+    }
+  ));
+  assert_eq!(reader.read_ascii_string(4).await?, "\u{ff}\u{ff}ab");
+
+  let mut reader = AsyncBytesReader::new(&*buffer);
+  reader.seek(SeekFrom::Start(4)).await?;
+  assert_eq!(reader.offset().await?, 4);
+  let err = reader.seek(SeekFrom::Start(2)).await.unwrap_err();
+  assert!(matches!(BinError::from(err), BinError::InvalidInput(_)));
+  let err = reader.read_bytes_no_move(100).await.unwrap_err();
+  match BinError::from(err) {
+    BinError::UnexpectedEof {
+      offset, requested, ..
+    } => assert_eq!((offset, requested), (4, 100)),
+    e => panic!("{}", e),
+  }
+  let err = reader.skip_ptr(100).await.unwrap_err();
+  assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+
+  // a short read is a typed UnexpectedEof, a stream goes back to the start of the read
+  let short = b"\x01\x02\x03".to_vec();
+  let mut reader = AsyncBytesReader::new(&*short);
+  reader.read_u8().await?;
+  let err = BinError::from(reader.read_u32_be().await.unwrap_err());
+  assert!(matches!(
+    err,
+    BinError::UnexpectedEof {
+      offset: 1,
+      requested: 4,
+      available: 2
+    }
+  ));
+  let mut reader = AsyncStreamReader::new(Cursor::new(short));
+  reader.read_u8().await?;
+  let err = BinError::from(reader.read_u32_be().await.unwrap_err());
+  assert!(matches!(
+    err,
+    BinError::UnexpectedEof {
+      offset: 1,
+      requested: 4,
+      available: 2
+    }
+  ));
+  assert_eq!(reader.read_u16_be().await?, 0x0203);
+
+  // futures are Send, a parser can be spawned
+  let handle = tokio::spawn(async move {
+    let mut reader = AsyncStreamReader::new(Cursor::new(buffer));
+    reader.read_u16_be().await
+  });
+  assert_eq!(handle.await??, 0x0102);
+
+  Ok(())
+}
+
+#[tokio::test]
+#[cfg(feature = "async")]
+pub async fn check_async_writer() -> Result<(), Box<dyn std::error::Error>> {
+  use crate::async_writer::*;
+  use crate::error::BinError;
+
+  async fn write<W: AsyncBinaryWriter>(writer: &mut W) -> Result<(), std::io::Error> {
+    writer.set_endian(Endian::BigEndian);
+    writer.write_u16(0x0102).await?;
+    writer.write_i32_le(-2).await?;
+    writer.write_f32_be(17.19).await?;
+    writer.write_uleb128(300).await?;
+    writer.write_ascii_string("bin").await?;
+    writer.write_utf16_string("AB").await?;
+    writer.write_utf16le_string("C").await?;
+    writer
+      .write_fixed_string("ab", 4, CodeType::Ascii, Padding::Space)
+      .await?;
+    writer.write_u64_le(u64::MAX).await?;
+    writer.flush().await
+  }
+
+  let mut sync = BytesWriter::new();
+  sync.set_endian(Endian::BigEndian);
+  sync.write_u16(0x0102)?;
+  sync.write_i32_le(-2)?;
+  sync.write_f32_be(17.19)?;
+  sync.write_uleb128(300)?;
+  sync.write_ascii_string("bin")?;
+  sync.write_utf16_string("AB")?;
+  sync.write_utf16le_string("C")?;
+  sync.write_fixed_string("ab", 4, CodeType::Ascii, Padding::Space)?;
+  sync.write_u64_le(u64::MAX)?;
+  let expected = sync.into_vec();
+
+  let mut writer = AsyncBytesWriter::new(Vec::new());
+  write(&mut writer).await?;
+  assert_eq!(writer.offset().await?, expected.len() as u64);
+  assert_eq!(writer.into_inner(), expected);
+
+  let mut writer = AsyncStreamWriter::new(Cursor::new(Vec::new()));
+  write(&mut writer).await?;
+  assert_eq!(writer.into_inner().into_inner(), expected);
+
+  // back-patching
+  let mut writer = AsyncStreamWriter::new(Cursor::new(Vec::new()));
+  let size = writer.reserve_u32(Endian::BigEndian).await?;
+  let offset = writer.reserve_u16(Endian::LittleEndian).await?;
+  writer.write_bytes(b"IHDR").await?;
+  assert!(writer.finish().await.is_err());
+  writer.fill_length(&size).await?;
+  let err = writer.fill(&offset, 0x10000).await.unwrap_err();
+  assert!(matches!(BinError::from(err), BinError::Overflow { .. }));
+  // the placeholder is still pending, and can be filled again
+  assert!(writer.finish().await.is_err());
+  writer.fill_offset(&offset).await?;
+  writer.write_u8(0xff).await?;
+  writer.finish().await?;
+  assert_eq!(
+    writer.into_inner().into_inner(),
+    b"\x00\x00\x00\x06\x0a\x00IHDR\xff"
+  );
+
+  // a writer which can not seek can not reserve
+  let mut writer = AsyncBytesWriter::new(Vec::new());
+  let err = writer.reserve_u32(Endian::BigEndian).await.unwrap_err();
+  assert!(matches!(BinError::from(err), BinError::InvalidInput(_)));
+  assert_eq!(writer.offset().await?, 0);
+  writer.finish().await?;
+
+  let mut writer = AsyncBytesWriter::new(Vec::new());
+  writer.write_u8(0).await?;
+  let err = writer.write_ascii_string("a\u{3042}").await.unwrap_err();
+  match BinError::from(err) {
+    BinError::UnmappableCharacter { offset, .. } => assert_eq!(offset, 2),
+    e => panic!("{}", e),
+  }
+
+  #[cfg(feature = "codec")]
+  {
+    let mut writer = AsyncBytesWriter::new(Vec::new());
+    writer
+      .write_local_string("日本語", CodeType::ShiftJis)
+      .await?;
+    assert_eq!(writer.into_inner(), b"\x93\xfa\x96\x7b\x8c\xea");
+  }
+
+  Ok(())
+}
+
+#[test]
+#[cfg(feature = "futures")]
+fn check_futures() -> Result<(), Box<dyn std::error::Error>> {
+  use crate::async_reader::*;
+  use crate::async_writer::*;
+  use crate::error::BinError;
+  use futures_executor::block_on;
+
+  // the same parser runs on tokio and futures-io readers
+  async fn read<R: AsyncBinaryReader>(
+    reader: &mut R,
+  ) -> Result<(u32, String, i64), std::io::Error> {
+    reader.set_endian(Endian::LittleEndian);
+    let size = reader.read_u32().await?;
+    let name = reader.read_utf16be_string(4).await?;
+    let value = reader.read_sleb128().await?;
+    Ok((size, name, value))
+  }
+
+  async fn write<W: AsyncBinaryWriter>(writer: &mut W) -> Result<(), std::io::Error> {
+    let size = writer.reserve_u32(Endian::LittleEndian).await?;
+    writer.write_utf16be_string("OK").await?;
+    writer.write_sleb128(-129).await?;
+    writer.fill_length(&size).await?;
+    writer.finish().await
+  }
+
+  block_on(async {
+    let mut writer = FuturesStreamWriter::new(futures_util::io::Cursor::new(Vec::new()));
+    write(&mut writer).await?;
+    let buffer = writer.into_inner().into_inner();
+    assert_eq!(buffer, b"\x06\x00\x00\x00\x00O\x00K\xff\x7e");
+
+    let mut reader = FuturesBytesReader::new(&*buffer);
+    assert_eq!(read(&mut reader).await?, (6, "OK".to_string(), -129));
+    assert_eq!(reader.offset().await?, 10);
+    let err = BinError::from(reader.read_u8().await.unwrap_err());
+    assert_eq!(err.offset(), Some(10));
+
+    let mut reader = FuturesStreamReader::new(futures_util::io::Cursor::new(buffer.clone()));
+    assert_eq!(read(&mut reader).await?, (6, "OK".to_string(), -129));
+    let err = BinError::from(reader.read_u16().await.unwrap_err());
+    assert_eq!(err.offset(), Some(10));
+    reader.seek(SeekFrom::Start(4)).await?;
+    assert_eq!(reader.read_bytes_no_move(2).await?, b"\x00O");
+    assert_eq!(reader.read_utf16_string(4).await?, "\u{4f00}\u{4b00}");
+
+    // skip_ptr seeks over bytes which are not buffered yet
+    let cursor = futures_util::io::Cursor::new(buffer.clone());
+    let mut reader =
+      FuturesStreamReader::new(futures_util::io::BufReader::with_capacity(2, cursor));
+    assert_eq!(reader.skip_ptr(5).await?, 5);
+    assert_eq!(reader.read_u8().await?, b'O');
+    let err = BinError::from(reader.skip_ptr(5).await.unwrap_err());
+    assert!(matches!(
+      err,
+      BinError::UnexpectedEof {
+        offset: 6,
+        requested: 5,
+        available: 4
+      }
+    ));
+    assert_eq!(reader.read_u8().await?, 0);
+
+    let mut reader = FuturesBytesReader::new(&*buffer);
+    reader.skip_ptr(4).await?;
+    let err = reader.seek(SeekFrom::Start(0)).await.unwrap_err();
+    assert!(matches!(BinError::from(err), BinError::InvalidInput(_)));
+    let err = BinError::from(reader.skip_ptr(7).await.unwrap_err());
+    assert!(matches!(
+      err,
+      BinError::UnexpectedEof {
+        offset: 4,
+        requested: 7,
+        available: 6
+      }
+    ));
+
+    let mut writer = FuturesBytesWriter::new(Vec::new());
+    writer.write_u16_be(0x0102).await?;
+    writer.write_ascii_string("bin").await?;
+    let err = writer.reserve_u16(Endian::BigEndian).await.unwrap_err();
+    assert!(matches!(BinError::from(err), BinError::InvalidInput(_)));
+    writer.finish().await?;
+    assert_eq!(writer.into_inner(), b"\x01\x02bin");
+
+    #[cfg(feature = "async")]
+    {
+      let mut reader = AsyncBytesReader::new(&*buffer);
+      assert_eq!(read(&mut reader).await?, (6, "OK".to_string(), -129));
+    }
+    Ok(())
+  })
+}
+
+#[test]
+#[cfg(feature = "framed")]
+fn check_framed() -> Result<(), Box<dyn std::error::Error>> {
+  use crate::error::BinError;
+  use crate::framed::BinCodec;
+  use tokio_util::bytes::BytesMut;
+  use tokio_util::codec::{Decoder, Encoder};
+
+  // TLV: u8 tag, u32 length, value
+  fn parse<R: BinaryReader>(reader: &mut R) -> Result<(u8, Vec<u8>), std::io::Error> {
+    let tag = reader.read_u8()?;
+    if tag == 0 {
+      let message = "tag 0 is reserved".to_string();
+      return Err(BinError::InvalidData { offset: 0, message }.into());
+    }
+    let len = reader.read_u32()? as u64;
+    let mut value = reader.sub_reader(len)?;
+    let value = value.read_bytes_as_vec(len as usize)?;
+    Ok((tag, value))
+  }
+
+  let mut codec = BinCodec::new(
+    |reader: &mut SliceReader| parse(reader),
+    |(tag, value): (u8, Vec<u8>), writer: &mut BytesWriter| {
+      writer.write_u8(tag)?;
+      let len = writer.reserve_u32(writer.endian())?;
+      writer.write_bytes(&value)?;
+      writer.fill_length(&len)
+    },
+  );
+  codec.set_endian(Endian::BigEndian);
+
+  let mut stream = BytesMut::new();
+  codec.encode((1, b"abc".to_vec()), &mut stream)?;
+  codec.encode((2, Vec::new()), &mut stream)?;
+  assert_eq!(&stream[..], b"\x01\x00\x00\x00\x03abc\x02\x00\x00\x00\x00");
+
+  // feed one byte at a time
+  let mut buffer = BytesMut::new();
+  let mut frames = Vec::new();
+  for b in stream.iter() {
+    buffer.extend_from_slice(&[*b]);
+    while let Some(frame) = codec.decode(&mut buffer)? {
+      frames.push(frame);
+    }
+  }
+  assert_eq!(frames, [(1, b"abc".to_vec()), (2, Vec::new())]);
+  assert!(buffer.is_empty());
+  assert_eq!(codec.decode(&mut buffer)?, None);
+
+  // a partial frame is not consumed
+  let mut buffer = BytesMut::from(&b"\x01\x00\x00\x00\x08abc"[..]);
+  assert_eq!(codec.decode(&mut buffer)?, None);
+  assert_eq!(buffer.len(), 8);
+  // a truncated frame is partial even when the read is in a sub reader
+  let mut codec2 = BinCodec::decoder(|reader: &mut SliceReader| {
+    let len = reader.read_u8()? as u64;
+    let mut value = reader.sub_reader(len)?;
+    value.read_u16_be()
+  });
+  let mut truncated = BytesMut::from(&b"\x02\x01"[..]);
+  assert_eq!(codec2.decode(&mut truncated)?, None);
+  truncated.extend_from_slice(b"\x02");
+  assert_eq!(codec2.decode(&mut truncated)?, Some(0x0102));
+  // an overrun of a sub reader in a complete frame is malformed, not partial
+  let mut malformed = BytesMut::from(&b"\x01\x01\x02\x03"[..]);
+  let err = codec2.decode(&mut malformed).unwrap_err();
+  assert!(matches!(BinError::from(err), BinError::InvalidData { .. }));
+  assert_eq!(malformed.len(), 4);
+  // a line or a C string without the delimiter yet is partial
+  let mut lines = BinCodec::decoder(|reader: &mut SliceReader| reader.read_line());
+  let mut pending = BytesMut::from(&b"hel"[..]);
+  assert_eq!(lines.decode(&mut pending)?, None);
+  assert_eq!(pending.len(), 3);
+  pending.extend_from_slice(b"lo\r\nw");
+  assert_eq!(lines.decode(&mut pending)?, Some(Some("hello".to_string())));
+  assert_eq!(&pending[..], b"w");
+  let mut cstrings = BinCodec::decoder(|reader: &mut SliceReader| reader.read_cstring());
+  let mut pending = BytesMut::from(&b"abc"[..]);
+  assert_eq!(cstrings.decode(&mut pending)?, None);
+  pending.extend_from_slice(b"\x00");
+  assert_eq!(cstrings.decode(&mut pending)?, Some("abc".to_string()));
+
+  codec.set_max_length(8);
+  let err = codec.decode(&mut buffer).unwrap_err();
+  assert!(matches!(BinError::from(err), BinError::InvalidData { .. }));
+
+  let mut buffer = BytesMut::from(&b"\x00\x00"[..]);
+  let err = codec.decode(&mut buffer).unwrap_err();
+  assert!(matches!(BinError::from(err), BinError::InvalidData { .. }));
+  assert_eq!(buffer.len(), 2);
+
+  let mut codec = BinCodec::decoder(|_: &mut SliceReader| Ok(()));
+  let mut buffer = BytesMut::from(&b"\x00"[..]);
+  assert!(codec.decode(&mut buffer).is_err());
+
+  // unfilled placeholders are errors
+  let mut codec = BinCodec::encoder(|len: u32, writer: &mut BytesWriter| {
+    writer.reserve_u32(Endian::LittleEndian)?;
+    writer.write_u32(len)
+  });
+  let mut buffer = BytesMut::new();
+  let err = codec.encode(1, &mut buffer).unwrap_err();
+  assert!(matches!(
+    BinError::from(err),
+    BinError::UnfilledPlaceholders { .. }
+  ));
+  assert!(buffer.is_empty());
+
+  Ok(())
+}
+
+#[test]
+fn check_incremental() -> Result<(), Box<dyn std::error::Error>> {
+  use crate::error::BinError;
+  use std::io::ErrorKind;
+
+  // the default mode is unchanged
+  let mut reader = BytesReader::new(b"\x01\x02");
+  assert!(!reader.is_incremental());
+  let err = reader.read_u32_be().unwrap_err();
+  assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+  let err = reader.read_bytes_as_vec(usize::MAX).unwrap_err();
+  assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+  let mut reader = BytesReader::new(b"\x01\x02");
+  reader.set_incremental(true);
+  reader.set_endian(Endian::BigEndian);
+  let checkpoint = reader.checkpoint();
+  assert_eq!(reader.read_u8()?, 1);
+  let err = reader.read_u32().unwrap_err();
+  assert_eq!(err.kind(), ErrorKind::WouldBlock);
+  match BinError::from(err) {
+    BinError::Incomplete { offset, needed } => assert_eq!((offset, needed), (1, 3)),
+    e => panic!("{}", e),
+  }
+  // a failed read does not move
+  assert_eq!(reader.offset()?, 1);
+  reader.rollback(checkpoint)?;
+  assert_eq!(checkpoint.offset(), 0);
+  reader.append(b"\x03\x04\x05");
+  assert_eq!(reader.read_u8()?, 1);
+  assert_eq!(reader.read_u32()?, 0x02030405);
+
+  // other reads
+  let err = reader.skip_ptr(1).unwrap_err();
+  assert!(matches!(
+    BinError::from(err),
+    BinError::Incomplete { needed: 1, .. }
+  ));
+  let err = reader.read_bytes_no_move(2).unwrap_err();
+  assert!(matches!(
+    BinError::from(err),
+    BinError::Incomplete { needed: 2, .. }
+  ));
+  let err = BinaryReader::seek(&mut reader, SeekFrom::Current(2)).unwrap_err();
+  assert!(matches!(
+    BinError::from(err),
+    BinError::Incomplete { needed: 2, .. }
+  ));
+  // the end of the buffered data is a valid position
+  assert_eq!(BinaryReader::seek(&mut reader, SeekFrom::End(0))?, 5);
+  let err = reader.read_bytes_as_vec(usize::MAX).unwrap_err();
+  assert!(matches!(
+    BinError::from(err),
+    BinError::Incomplete {
+      offset: 5,
+      needed: usize::MAX
+    }
+  ));
+
+  // read_until waits for the delimiter
+  reader.append(b"line");
+  let checkpoint = reader.checkpoint();
+  let err = reader.read_line().unwrap_err();
+  assert!(matches!(
+    BinError::from(err),
+    BinError::Incomplete {
+      offset: 5,
+      needed: 1
+    }
+  ));
+  reader.rollback(checkpoint)?;
+  reader.append(b"1\nnul");
+  assert_eq!(reader.read_line()?, Some("line1".to_string()));
+  let err = reader.read_cstring().unwrap_err();
+  assert!(matches!(BinError::from(err), BinError::Incomplete { .. }));
+  reader.append(b"\0last");
+  assert_eq!(reader.read_cstring()?, "nul");
+
+  // contexts keep the error, match it through root
+  let err = reader.with_context("record", |r| r.read_u64()).unwrap_err();
+  assert_eq!(err.kind(), ErrorKind::WouldBlock);
+  let err = BinError::from(err);
+  assert!(!matches!(err, BinError::Incomplete { .. }));
+  assert!(matches!(err.root(), BinError::Incomplete { needed: 4, .. }));
+
+  // compact drops the bytes read, and keeps later checkpoints
+  let last = reader.checkpoint();
+  assert_eq!(reader.read_u8()?, b'l');
+  reader.compact();
+  assert_eq!(reader.offset()?, 0);
+  assert!(reader.rollback(checkpoint).is_err());
+  let err = BinError::from(reader.rollback(last).unwrap_err());
+  assert!(matches!(err, BinError::SeekOutOfRange { position: -1, .. }));
+  assert_eq!(reader.read_bytes_no_move(3)?, b"ast");
+  let checkpoint = reader.checkpoint();
+  assert_eq!(checkpoint.offset(), last.offset() + 1);
+  reader.compact();
+  assert_eq!(reader.read_u16_be()?, 0x6173);
+  reader.rollback(checkpoint)?;
+  assert_eq!(reader.read_bytes_no_move(3)?, b"ast");
+
+  // the rest is read as usual after the end of data
+  reader.set_incremental(false);
+  assert_eq!(reader.read_line()?, Some("ast".to_string()));
+  assert_eq!(reader.read_line()?, None);
+  let err = reader.read_u8().unwrap_err();
+  assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+  // SliceReader has the same mode
+  let mut reader = SliceReader::new(b"\x01ab");
+  reader.set_incremental(true);
+  assert!(reader.is_incremental());
+  assert_eq!(reader.read_u8()?, 1);
+  let err = reader.read_u32_be().unwrap_err();
+  assert!(matches!(
+    BinError::from(err),
+    BinError::Incomplete {
+      offset: 1,
+      needed: 2
+    }
+  ));
+  let err = reader.read_line().unwrap_err();
+  assert_eq!(err.kind(), ErrorKind::WouldBlock);
+  assert_eq!(reader.offset()?, 1);
+  assert_eq!(reader.seek(SeekFrom::End(0))?, 3);
+  let err = reader.seek(SeekFrom::Current(1)).unwrap_err();
+  assert_eq!(err.kind(), ErrorKind::WouldBlock);
+  reader.set_incremental(false);
+  reader.seek(SeekFrom::Start(1))?;
+  assert_eq!(reader.read_line()?, Some("ab".to_string()));
+
+  Ok(())
+}
+
 #[cfg(feature = "util")]
+#[allow(dead_code)]
 fn io_test() {
   todo!();
 }
@@ -0,0 +1,168 @@
+//! Variable-length integer encoders shared by `writer` and `io`,
+//! and decoders shared by `reader` and `async_reader`.
+
+use crate::error::Malformed;
+
+pub(crate) fn encode_uleb128(num: u64, buf: &mut Vec<u8>) {
+  let mut num = num;
+  loop {
+    let b = (num & 0x7f) as u8;
+    num >>= 7;
+    if num == 0 {
+      buf.push(b);
+      return;
+    }
+    buf.push(b | 0x80);
+  }
+}
+
+pub(crate) fn encode_sleb128(num: i64, buf: &mut Vec<u8>) {
+  let mut num = num;
+  loop {
+    let b = (num & 0x7f) as u8;
+    num >>= 7;
+    if (num == 0 && b & 0x40 == 0) || (num == -1 && b & 0x40 != 0) {
+      buf.push(b);
+      return;
+    }
+    buf.push(b | 0x80);
+  }
+}
+
+pub(crate) fn encode_zigzag_varint(num: i64, buf: &mut Vec<u8>) {
+  encode_uleb128(((num << 1) ^ (num >> 63)) as u64, buf)
+}
+
+pub(crate) fn encode_vlq(num: u64, buf: &mut Vec<u8>) {
+  let bits = 64 - (num | 1).leading_zeros();
+  let mut shift = bits.div_ceil(7) * 7 - 7;
+  while shift > 0 {
+    buf.push(((num >> shift) & 0x7f) as u8 | 0x80);
+    shift -= 7;
+  }
+  buf.push((num & 0x7f) as u8);
+}
+
+// Varint decodes a variable-length integer byte by byte.
+pub(crate) trait Varint: Default {
+  type Value;
+
+  // push takes the next byte, and returns the value after the last byte.
+  fn push(&mut self, b: u8) -> Result<Option<Self::Value>, Malformed>;
+}
+
+// Uleb128 rejects overlong encodings and values over u64.
+#[derive(Default)]
+pub(crate) struct Uleb128 {
+  value: u64,
+  len: usize,
+}
+
+impl Varint for Uleb128 {
+  type Value = u64;
+
+  fn push(&mut self, b: u8) -> Result<Option<u64>, Malformed> {
+    let shift = self.len * 7;
+    self.len += 1;
+    let payload = (b & 0x7f) as u64;
+    if shift == 63 && (payload > 1 || b & 0x80 != 0) {
+      let message = "uleb128 overflows u64";
+      return Err(Malformed::Overflow {
+        len: self.len,
+        message,
+      });
+    }
+    self.value |= payload << shift;
+    if b & 0x80 != 0 {
+      return Ok(None);
+    }
+    if b == 0 && shift > 0 {
+      let message = "overlong uleb128 encoding";
+      return Err(Malformed::InvalidData {
+        len: self.len,
+        message,
+      });
+    }
+    Ok(Some(self.value))
+  }
+}
+
+// Sleb128 rejects overlong encodings and values over i64.
+#[derive(Default)]
+pub(crate) struct Sleb128 {
+  value: i64,
+  len: usize,
+  prev: u8,
+}
+
+impl Varint for Sleb128 {
+  type Value = i64;
+
+  fn push(&mut self, b: u8) -> Result<Option<i64>, Malformed> {
+    let shift = self.len * 7;
+    self.len += 1;
+    let payload = (b & 0x7f) as i64;
+    if shift == 63 && (b & 0x80 != 0 || (payload != 0 && payload != 0x7f)) {
+      let message = "sleb128 overflows i64";
+      return Err(Malformed::Overflow {
+        len: self.len,
+        message,
+      });
+    }
+    self.value |= payload << shift;
+    if b & 0x80 != 0 {
+      self.prev = b;
+      return Ok(None);
+    }
+    let prev = self.prev;
+    if shift > 0 && ((b == 0 && prev & 0x40 == 0) || (b == 0x7f && prev & 0x40 != 0)) {
+      let message = "overlong sleb128 encoding";
+      return Err(Malformed::InvalidData {
+        len: self.len,
+        message,
+      });
+    }
+    if shift + 7 < 64 && b & 0x40 != 0 {
+      self.value |= -1 << (shift + 7);
+    }
+    Ok(Some(self.value))
+  }
+}
+
+// Vlq is big-endian, and rejects leading 0x80 bytes and values over u64.
+#[derive(Default)]
+pub(crate) struct Vlq {
+  value: u64,
+  len: usize,
+}
+
+impl Varint for Vlq {
+  type Value = u64;
+
+  fn push(&mut self, b: u8) -> Result<Option<u64>, Malformed> {
+    self.len += 1;
+    if self.len == 1 && b == 0x80 {
+      let message = "overlong vlq encoding";
+      return Err(Malformed::InvalidData {
+        len: self.len,
+        message,
+      });
+    }
+    if self.value >> 57 != 0 {
+      let message = "vlq overflows u64";
+      return Err(Malformed::Overflow {
+        len: self.len,
+        message,
+      });
+    }
+    self.value = (self.value << 7) | (b & 0x7f) as u64;
+    if b & 0x80 != 0 {
+      return Ok(None);
+    }
+    Ok(Some(self.value))
+  }
+}
+
+pub(crate) fn decode_zigzag(value: u64) -> i64 {
+  (value >> 1) as i64 ^ -((value & 1) as i64)
+}
@@ -0,0 +1,550 @@
+use crate::error::BinError;
+use crate::reader::CodeType;
+use crate::Endian;
+use std::io::{Error, SeekFrom};
+
+use super::{EncodeOptions, Padding, Placeholder, Placeholders, ToBinary, Unmappable};
+
+/// BinaryWriter is the counterpart of `BinaryReader`.
+///
+/// An implementation only has to provide `write_bytes`, endian handling,
+/// `offset`, `seek` and `placeholders`; numbers and strings are written by this trait.
+pub trait BinaryWriter {
+  fn set_endian(&mut self, endian: Endian);
+  fn endian(&self) -> Endian;
+
+  /// write_bytes writes whole `array` at the current offset.
+  fn write_bytes(&mut self, array: &[u8]) -> Result<(), Error>;
+
+  fn write_byte(&mut self, num: u8) -> Result<(), Error> {
+    self.write_bytes(&[num])
+  }
+
+  fn write_u8(&mut self, num: u8) -> Result<(), Error> {
+    self.write_byte(num)
+  }
+
+  fn write_i8(&mut self, num: i8) -> Result<(), Error> {
+    self.write_byte(num as u8)
+  }
+
+  fn write_u16(&mut self, num: u16) -> Result<(), Error> {
+    match self.endian() {
+      Endian::BigEndian => self.write_u16_be(num),
+      Endian::LittleEndian => self.write_u16_le(num),
+    }
+  }
+
+  fn write_u32(&mut self, num: u32) -> Result<(), Error> {
+    match self.endian() {
+      Endian::BigEndian => self.write_u32_be(num),
+      Endian::LittleEndian => self.write_u32_le(num),
+    }
+  }
+
+  fn write_u64(&mut self, num: u64) -> Result<(), Error> {
+    match self.endian() {
+      Endian::BigEndian => self.write_u64_be(num),
+      Endian::LittleEndian => self.write_u64_le(num),
+    }
+  }
+
+  fn write_u128(&mut self, num: u128) -> Result<(), Error> {
+    match self.endian() {
+      Endian::BigEndian => self.write_u128_be(num),
+      Endian::LittleEndian => self.write_u128_le(num),
+    }
+  }
+
+  fn write_i16(&mut self, num: i16) -> Result<(), Error> {
+    match self.endian() {
+      Endian::BigEndian => self.write_i16_be(num),
+      Endian::LittleEndian => self.write_i16_le(num),
+    }
+  }
+
+  fn write_i32(&mut self, num: i32) -> Result<(), Error> {
+    match self.endian() {
+      Endian::BigEndian => self.write_i32_be(num),
+      Endian::LittleEndian => self.write_i32_le(num),
+    }
+  }
+
+  fn write_i64(&mut self, num: i64) -> Result<(), Error> {
+    match self.endian() {
+      Endian::BigEndian => self.write_i64_be(num),
+      Endian::LittleEndian => self.write_i64_le(num),
+    }
+  }
+
+  fn write_i128(&mut self, num: i128) -> Result<(), Error> {
+    match self.endian() {
+      Endian::BigEndian => self.write_i128_be(num),
+      Endian::LittleEndian => self.write_i128_le(num),
+    }
+  }
+
+  fn write_f32(&mut self, num: f32) -> Result<(), Error> {
+    match self.endian() {
+      Endian::BigEndian => self.write_f32_be(num),
+      Endian::LittleEndian => self.write_f32_le(num),
+    }
+  }
+
+  fn write_f64(&mut self, num: f64) -> Result<(), Error> {
+    match self.endian() {
+      Endian::BigEndian => self.write_f64_be(num),
+      Endian::LittleEndian => self.write_f64_le(num),
+    }
+  }
+
+  fn write_u16_be(&mut self, num: u16) -> Result<(), Error> {
+    self.write_bytes(&num.to_be_bytes())
+  }
+
+  fn write_u32_be(&mut self, num: u32) -> Result<(), Error> {
+    self.write_bytes(&num.to_be_bytes())
+  }
+
+  fn write_u64_be(&mut self, num: u64) -> Result<(), Error> {
+    self.write_bytes(&num.to_be_bytes())
+  }
+
+  fn write_u128_be(&mut self, num: u128) -> Result<(), Error> {
+    self.write_bytes(&num.to_be_bytes())
+  }
+
+  fn write_i16_be(&mut self, num: i16) -> Result<(), Error> {
+    self.write_bytes(&num.to_be_bytes())
+  }
+
+  fn write_i32_be(&mut self, num: i32) -> Result<(), Error> {
+    self.write_bytes(&num.to_be_bytes())
+  }
+
+  fn write_i64_be(&mut self, num: i64) -> Result<(), Error> {
+    self.write_bytes(&num.to_be_bytes())
+  }
+
+  fn write_i128_be(&mut self, num: i128) -> Result<(), Error> {
+    self.write_bytes(&num.to_be_bytes())
+  }
+
+  fn write_f32_be(&mut self, num: f32) -> Result<(), Error> {
+    self.write_bytes(&num.to_be_bytes())
+  }
+
+  fn write_f64_be(&mut self, num: f64) -> Result<(), Error> {
+    self.write_bytes(&num.to_be_bytes())
+  }
+
+  fn write_u16_le(&mut self, num: u16) -> Result<(), Error> {
+    self.write_bytes(&num.to_le_bytes())
+  }
+
+  fn write_u32_le(&mut self, num: u32) -> Result<(), Error> {
+    self.write_bytes(&num.to_le_bytes())
+  }
+
+  fn write_u64_le(&mut self, num: u64) -> Result<(), Error> {
+    self.write_bytes(&num.to_le_bytes())
+  }
+
+  fn write_u128_le(&mut self, num: u128) -> Result<(), Error> {
+    self.write_bytes(&num.to_le_bytes())
+  }
+
+  fn write_i16_le(&mut self, num: i16) -> Result<(), Error> {
+    self.write_bytes(&num.to_le_bytes())
+  }
+
+  fn write_i32_le(&mut self, num: i32) -> Result<(), Error> {
+    self.write_bytes(&num.to_le_bytes())
+  }
+
+  fn write_i64_le(&mut self, num: i64) -> Result<(), Error> {
+    self.write_bytes(&num.to_le_bytes())
+  }
+
+  fn write_i128_le(&mut self, num: i128) -> Result<(), Error> {
+    self.write_bytes(&num.to_le_bytes())
+  }
+
+  fn write_f32_le(&mut self, num: f32) -> Result<(), Error> {
+    self.write_bytes(&num.to_le_bytes())
+  }
+
+  fn write_f64_le(&mut self, num: f64) -> Result<(), Error> {
+    self.write_bytes(&num.to_le_bytes())
+  }
+
+  /// write_value writes any ToBinary type with the writer's endian.
+  ///
+  /// It is not named `write` to avoid a conflict with `std::io::Write::write`.
+  fn write_value<T: ToBinary>(&mut self, value: &T) -> Result<(), Error> {
+    let endian = self.endian();
+    value.write_to(self, endian)
+  }
+
+  /// write_value_with writes any ToBinary type with `endian`.
+  fn write_value_with<T: ToBinary>(&mut self, value: &T, endian: Endian) -> Result<(), Error> {
+    value.write_to(self, endian)
+  }
+
+  /// write_uleb128 writes unsigned LEB128 in the shortest form.
+  fn write_uleb128(&mut self, num: u64) -> Result<(), Error> {
+    let mut array = Vec::new();
+    crate::varint::encode_uleb128(num, &mut array);
+    self.write_bytes(&array)
+  }
+
+  /// write_sleb128 writes signed LEB128 in the shortest form.
+  fn write_sleb128(&mut self, num: i64) -> Result<(), Error> {
+    let mut array = Vec::new();
+    crate::varint::encode_sleb128(num, &mut array);
+    self.write_bytes(&array)
+  }
+
+  /// write_zigzag_varint writes protobuf style sint64.
+  fn write_zigzag_varint(&mut self, num: i64) -> Result<(), Error> {
+    let mut array = Vec::new();
+    crate::varint::encode_zigzag_varint(num, &mut array);
+    self.write_bytes(&array)
+  }
+
+  /// write_vlq writes a big-endian variable-length quantity (MIDI).
+  fn write_vlq(&mut self, num: u64) -> Result<(), Error> {
+    let mut array = Vec::new();
+    crate::varint::encode_vlq(num, &mut array);
+    self.write_bytes(&array)
+  }
+
+  /// write_ascii_string writes one byte per charactor, the counterpart of `read_ascii_string`.
+  /// The end marker 0x00 is not written. Returns the number of bytes.
+  fn write_ascii_string(&mut self, string: &str) -> Result<usize, Error> {
+    let array = match ascii_bytes(string) {
+      Ok(array) => array,
+      Err((character, position)) => {
+        let offset = self.offset()? + position as u64;
+        return Err(unmappable(offset, "US-ASCII", character));
+      }
+    };
+    self.write_bytes(&array)?;
+    Ok(array.len())
+  }
+
+  /// write_utf16_string writes utf16 string. use endien
+  /// Returns the number of bytes.
+  fn write_utf16_string(&mut self, string: &str) -> Result<usize, Error> {
+    let mut size = 0;
+    for c in string.encode_utf16() {
+      self.write_u16(c)?;
+      size += 2;
+    }
+    Ok(size)
+  }
+
+  fn write_utf16be_string(&mut self, string: &str) -> Result<usize, Error> {
+    let endian = self.endian();
+    self.set_endian(Endian::BigEndian);
+    let result = self.write_utf16_string(string);
+    self.set_endian(endian);
+    result
+  }
+
+  fn write_utf16le_string(&mut self, string: &str) -> Result<usize, Error> {
+    let endian = self.endian();
+    self.set_endian(Endian::LittleEndian);
+    let result = self.write_utf16_string(string);
+    self.set_endian(endian);
+    result
+  }
+
+  /// write_utf16_string_with writes utf16 string in `endian` with an optional BOM and NUL.
+  /// Returns the number of bytes.
+  /// ```
+  /// use bin_rs::writer::*;
+  /// use bin_rs::Endian;
+  /// use std::io::Error;
+  ///
+  /// fn test() -> Result<(), Error> {
+  ///   let mut writer = BytesWriter::new();
+  ///   let options = EncodeOptions {
+  ///     bom: true,
+  ///     nul_terminated: true,
+  ///     ..Default::default()
+  ///   };
+  ///   let size = writer.write_utf16_string_with("Hi", Endian::LittleEndian, options)?;
+  ///   assert_eq!(size, 8);
+  ///   assert_eq!(writer.as_slice(), b"\xff\xfeH\x00i\x00\x00\x00");
+  ///   Ok(())
+  /// }
+  /// ```
+  fn write_utf16_string_with(
+    &mut self,
+    string: &str,
+    endian: Endian,
+    options: EncodeOptions,
+  ) -> Result<usize, Error> {
+    let array = utf16_bytes(string, endian, options);
+    self.write_bytes(&array)?;
+    Ok(array.len())
+  }
+
+  fn write_utf8_string(&mut self, string: &str) -> Result<usize, Error> {
+    self.write_bytes(string.as_bytes())?;
+    Ok(string.len())
+  }
+
+  /// write_fixed_string writes `string` in `code` into a `width` bytes field filled with `padding`.
+  /// It is an error if the encoded string is longer than `width`. UTF-16 needs an even `width`.
+  /// ```
+  /// use bin_rs::reader::CodeType;
+  /// use bin_rs::writer::*;
+  /// use std::io::Error;
+  ///
+  /// fn test() -> Result<(), Error> {
+  ///   let mut writer = BytesWriter::new();
+  ///   writer.write_fixed_string("README", 8, CodeType::Ascii, Padding::Space)?;
+  ///   writer.write_fixed_string("a.txt", 8, CodeType::Utf8, Padding::Nul)?;
+  ///   assert_eq!(writer.as_slice(), b"README  a.txt\x00\x00\x00");
+  ///   assert!(writer.write_fixed_string("too long", 4, CodeType::Ascii, Padding::Nul).is_err());
+  ///   Ok(())
+  /// }
+  /// ```
+  fn write_fixed_string(
+    &mut self,
+    string: &str,
+    width: usize,
+    code: CodeType,
+    padding: Padding,
+  ) -> Result<(), Error> {
+    crate::reader::check_supported(code)?;
+    let offset = self.offset()?;
+    let array = fixed_bytes(string, width, code, padding, offset)?;
+    self.write_bytes(&array)
+  }
+
+  /// write_local_string writes `string` in `code`, feature="codec".
+  /// Characters which `code` can not represent are errors. Returns the number of bytes.
+  /// ```
+  /// use bin_rs::reader::CodeType;
+  /// use bin_rs::writer::*;
+  /// use std::io::Error;
+  ///
+  /// fn test() -> Result<(), Error> {
+  ///   let mut writer = BytesWriter::new();
+  ///   writer.write_local_string("日本語.txt", CodeType::ShiftJis)?;
+  ///   assert_eq!(writer.as_slice(), b"\x93\xfa\x96\x7b\x8c\xea.txt");
+  ///   Ok(())
+  /// }
+  /// ```
+  #[cfg(feature = "codec")]
+  fn write_local_string(&mut self, string: &str, code: CodeType) -> Result<usize, Error> {
+    self.write_local_string_with(string, code, EncodeOptions::default())
+  }
+
+  /// write_local_string_with writes `string` in `code` with `options`, feature="codec".
+  /// Returns the number of bytes.
+  #[cfg(feature = "codec")]
+  fn write_local_string_with(
+    &mut self,
+    string: &str,
+    code: CodeType,
+    options: EncodeOptions,
+  ) -> Result<usize, Error> {
+    let array = match local_bytes(string, code, options) {
+      Ok(array) => array,
+      Err((character, position)) => {
+        let offset = self.offset()? + position as u64;
+        return Err(unmappable(offset, code.name(), character));
+      }
+    };
+    self.write_bytes(&array)?;
+    Ok(array.len())
+  }
+
+  fn offset(&mut self) -> Result<u64, Error>;
+  fn seek(&mut self, seek: SeekFrom) -> Result<u64, Error>;
+
+  /// flush writes out buffered data. default does nothing.
+  fn flush(&mut self) -> Result<(), Error> {
+    Ok(())
+  }
+
+  /// placeholders returns the unfilled placeholders of this writer.
+  fn placeholders(&mut self) -> &mut Placeholders;
+
+  /// reserve_u16 writes a zero u16 field and returns a handle to fill it later.
+  /// ```
+  /// use bin_rs::writer::*;
+  /// use bin_rs::Endian;
+  /// use std::io::Error;
+  ///
+  /// fn test() -> Result<Vec<u8>, Error> {
+  ///   let mut writer = BytesWriter::new();
+  ///   let size = writer.reserve_u32(Endian::BigEndian)?;
+  ///   writer.write_bytes(b"IHDR")?;
+  ///   writer.fill_length(&size)?; // 4
+  ///   writer.finish()?;
+  ///   Ok(writer.into_vec())
+  /// }
+  /// ```
+  fn reserve_u16(&mut self, endian: Endian) -> Result<Placeholder, Error> {
+    let offset = self.offset()?;
+    self.write_bytes(&[0; 2])?;
+    Ok(self.placeholders().reserve(offset, 2, endian))
+  }
+
+  fn reserve_u32(&mut self, endian: Endian) -> Result<Placeholder, Error> {
+    let offset = self.offset()?;
+    self.write_bytes(&[0; 4])?;
+    Ok(self.placeholders().reserve(offset, 4, endian))
+  }
+
+  fn reserve_u64(&mut self, endian: Endian) -> Result<Placeholder, Error> {
+    let offset = self.offset()?;
+    self.write_bytes(&[0; 8])?;
+    Ok(self.placeholders().reserve(offset, 8, endian))
+  }
+
+  /// fill writes `value` into the placeholder, and returns to the current offset.
+  /// The placeholder is pending until the write succeeds, so a failed fill can be retried.
+  fn fill(&mut self, placeholder: &Placeholder, value: u64) -> Result<(), Error> {
+    let bytes = placeholder.to_bytes(value)?;
+    self.placeholders().reserved(placeholder)?;
+    let current = self.offset()?;
+    self.seek(SeekFrom::Start(placeholder.offset()))?;
+    let written = self.write_bytes(&bytes);
+    if written.is_ok() {
+      self.placeholders().remove(placeholder)?;
+    }
+    self.seek(SeekFrom::Start(current))?;
+    written
+  }
+
+  /// fill_length fills the number of bytes written after the placeholder.
+  fn fill_length(&mut self, placeholder: &Placeholder) -> Result<(), Error> {
+    let current = self.offset()?;
+    let end = placeholder.end();
+    if current < end {
+      let s = format!(
+        "current offset {} is before the end of placeholder {}",
+        current, end
+      );
+      return Err(BinError::InvalidInput(s).into());
+    }
+    self.fill(placeholder, current - end)
+  }
+
+  /// fill_offset fills the current absolute offset.
+  fn fill_offset(&mut self, placeholder: &Placeholder) -> Result<(), Error> {
+    let current = self.offset()?;
+    self.fill(placeholder, current)
+  }
+
+  /// finish checks that all placeholders are filled, and flushes.
+  fn finish(&mut self) -> Result<(), Error> {
+    self.placeholders().check()?;
+    self.flush()
+  }
+}
+
+// ascii_bytes writes one byte per character up to U+00FF.
+// An unmappable character is returned with its byte position.
+pub(crate) fn ascii_bytes(string: &str) -> Result<Vec<u8>, (char, usize)> {
+  let mut array = Vec::with_capacity(string.len());
+  for c in string.chars() {
+    if c as u32 > 0xff {
+      return Err((c, array.len()));
+    }
+    array.push(c as u8);
+  }
+  Ok(array)
+}
+
+pub(crate) fn utf16_bytes(string: &str, endian: Endian, options: EncodeOptions) -> Vec<u8> {
+  let bom = options.bom.then_some(0xfeff);
+  let nul = options.nul_terminated.then_some(0);
+  let units: Vec<u16> = bom
+    .into_iter()
+    .chain(string.encode_utf16())
+    .chain(nul)
+    .collect();
+  match endian {
+    Endian::BigEndian => units.iter().flat_map(|c| c.to_be_bytes()).collect(),
+    Endian::LittleEndian => units.iter().flat_map(|c| c.to_le_bytes()).collect(),
+  }
+}
+
+// fixed_bytes encodes `string` into a `width` bytes field written at `offset`.
+pub(crate) fn fixed_bytes(
+  string: &str,
+  width: usize,
+  code: CodeType,
+  padding: Padding,
+  offset: u64,
+) -> Result<Vec<u8>, Error> {
+  crate::reader::check_width(width, code)?;
+  let mut array = match crate::codec::encode(string, code, Unmappable::Error) {
+    Ok(bytes) => bytes,
+    Err((character, position)) => {
+      return Err(unmappable(offset + position as u64, code.name(), character))
+    }
+  };
+  if array.len() > width {
+    let message = format!(
+      "{} bytes string does not fit in {} bytes",
+      array.len(),
+      width
+    );
+    return Err(BinError::Overflow { offset, message }.into());
+  }
+  let unit: &[u8] = match (padding, code) {
+    (Padding::Nul, CodeType::Utf16Be | CodeType::Utf16le) => &[0, 0],
+    (Padding::Nul, _) => &[0],
+    (Padding::Space, CodeType::Utf16Be) => &[0, 0x20],
+    (Padding::Space, CodeType::Utf16le) => &[0x20, 0],
+    (Padding::Space, _) => b" ",
+  };
+  while array.len() < width {
+    array.extend_from_slice(unit);
+  }
+  Ok(array)
+}
+
+// local_bytes encodes `string` in `code` with a BOM and NUL of `options`.
+// An unmappable character is returned with its byte position.
+#[cfg(feature = "codec")]
+pub(crate) fn local_bytes(
+  string: &str,
+  code: CodeType,
+  options: EncodeOptions,
+) -> Result<Vec<u8>, (char, usize)> {
+  let mut array = match (options.bom, code) {
+    (true, CodeType::Utf8) => vec![0xef, 0xbb, 0xbf],
+    (true, CodeType::Utf16Be) => vec![0xfe, 0xff],
+    (true, CodeType::Utf16le) => vec![0xff, 0xfe],
+    _ => Vec::new(),
+  };
+  match crate::codec::encode(string, code, options.unmappable) {
+    Ok(bytes) => array.extend(bytes),
+    Err((character, position)) => return Err((character, array.len() + position)),
+  }
+  if options.nul_terminated {
+    match code {
+      CodeType::Utf16Be | CodeType::Utf16le => array.extend([0, 0]),
+      _ => array.push(0),
+    }
+  }
+  Ok(array)
+}
+
+pub(crate) fn unmappable(offset: u64, encoding: &'static str, character: char) -> Error {
+  BinError::UnmappableCharacter {
+    offset,
+    encoding,
+    character,
+  }
+  .into()
+}
@@ -0,0 +1,138 @@
+use crate::error::BinError;
+use crate::BitOrder;
+use std::io::{Error, Write};
+
+/// BitWriter packs sub-byte fields into bytes, the counterpart of `BitReader`.
+///
+/// It sits on any `Write`, such as `BytesWriter`, `StreamWriter` or `Vec<u8>`.
+/// ```
+/// use bin_rs::writer::*;
+/// use bin_rs::BitOrder;
+/// use std::io::Error;
+///
+/// fn test() -> Result<(), Error> {
+///   let mut writer = BitWriter::new(BytesWriter::new(), BitOrder::MsbFirst);
+///   writer.write_bits(0b101, 3)?;
+///   writer.write_bit(true)?;
+///   writer.flush_with_padding(false)?;
+///   assert_eq!(writer.get_ref().as_slice(), [0b1011_0000]);
+///   Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct BitWriter<W> {
+  writer: W,
+  order: BitOrder,
+  cache: u128,
+  cache_bits: u32,
+  bit_position: u64,
+}
+
+impl<W: Write> BitWriter<W> {
+  pub fn new(writer: W, order: BitOrder) -> Self {
+    Self {
+      writer,
+      order,
+      cache: 0,
+      cache_bits: 0,
+      bit_position: 0,
+    }
+  }
+
+  pub fn order(&self) -> BitOrder {
+    self.order
+  }
+
+  /// bit_position returns the number of bits written since this BitWriter was created.
+  pub fn bit_position(&self) -> u64 {
+    self.bit_position
+  }
+
+  pub fn is_aligned(&self) -> bool {
+    self.bit_position % 8 == 0
+  }
+
+  pub fn get_ref(&self) -> &W {
+    &self.writer
+  }
+
+  pub fn get_mut(&mut self) -> &mut W {
+    &mut self.writer
+  }
+
+  /// into_inner pads the last byte with 0 bits and returns the inner writer.
+  pub fn into_inner(mut self) -> Result<W, Error> {
+    self.flush_with_padding(false)?;
+    Ok(self.writer)
+  }
+
+  /// write_bits writes the lower `n` bits of `value`, up to 64 bits.
+  /// It is an error if `value` does not fit in `n` bits.
+  pub fn write_bits(&mut self, value: u64, n: u32) -> Result<(), Error> {
+    if n > 64 {
+      let s = format!("write bits must be 64 bits or less, but {}", n);
+      return Err(BinError::InvalidInput(s).into());
+    }
+    if value as u128 > mask(n) {
+      let offset = self.bit_position / 8;
+      let message = format!("value {} does not fit in {} bits", value, n);
+      return Err(BinError::Overflow { offset, message }.into());
+    }
+    match self.order {
+      BitOrder::MsbFirst => self.cache = (self.cache << n) | value as u128,
+      BitOrder::LsbFirst => self.cache |= (value as u128) << self.cache_bits,
+    }
+    self.cache_bits += n;
+    self.bit_position += n as u64;
+
+    let mut bytes = Vec::with_capacity(self.cache_bits as usize / 8);
+    while self.cache_bits >= 8 {
+      match self.order {
+        BitOrder::MsbFirst => {
+          bytes.push((self.cache >> (self.cache_bits - 8)) as u8);
+          self.cache_bits -= 8;
+          self.cache &= mask(self.cache_bits);
+        }
+        BitOrder::LsbFirst => {
+          bytes.push(self.cache as u8);
+          self.cache >>= 8;
+          self.cache_bits -= 8;
+        }
+      }
+    }
+    self.writer.write_all(&bytes)
+  }
+
+  pub fn write_bit(&mut self, bit: bool) -> Result<(), Error> {
+    self.write_bits(bit as u64, 1)
+  }
+
+  /// write_signed_bits writes the lower `n` bits of a two's complement number.
+  pub fn write_signed_bits(&mut self, value: i64, n: u32) -> Result<(), Error> {
+    let fits = match n {
+      0 => value == 0,
+      1..=63 => (-(1 << (n - 1))..(1 << (n - 1))).contains(&value),
+      _ => true,
+    };
+    if !fits {
+      let offset = self.bit_position / 8;
+      let message = format!("value {} does not fit in {} bits", value, n);
+      return Err(BinError::Overflow { offset, message }.into());
+    }
+    self.write_bits(value as u64 & mask(n) as u64, n)
+  }
+
+  /// flush_with_padding fills the rest of the current byte with `bit`, and flushes.
+  pub fn flush_with_padding(&mut self, bit: bool) -> Result<(), Error> {
+    if self.cache_bits > 0 {
+      let pad = 8 - self.cache_bits;
+      let value = if bit { mask(pad) as u64 } else { 0 };
+      self.write_bits(value, pad)?;
+    }
+    self.writer.flush()
+  }
+}
+
+fn mask(n: u32) -> u128 {
+  (1 << n) - 1
+}
@@ -0,0 +1,141 @@
+use crate::error::BinError;
+use crate::Endian;
+use std::io::{Error, ErrorKind, SeekFrom, Write};
+
+use super::{BinaryWriter, Placeholders};
+
+/// BytesWriter writes into an owned `Vec<u8>`.
+///
+/// Writing after a backward seek overwrites the existing bytes,
+/// writing at the end grows the buffer.
+/// Seeking past the end only moves the offset like `Cursor<Vec<u8>>`,
+/// the next write fills the gap with 0x00.
+#[derive(Debug, Clone)]
+pub struct BytesWriter {
+  buffer: Vec<u8>,
+  ptr: usize,
+  endian: Endian,
+  placeholders: Placeholders,
+}
+
+impl BytesWriter {
+  pub fn new() -> Self {
+    Self::from(Vec::new())
+  }
+
+  pub fn with_capacity(capacity: usize) -> Self {
+    Self::from(Vec::with_capacity(capacity))
+  }
+
+  pub fn len(&self) -> usize {
+    self.buffer.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.buffer.is_empty()
+  }
+
+  pub fn as_slice(&self) -> &[u8] {
+    &self.buffer
+  }
+
+  pub fn into_vec(self) -> Vec<u8> {
+    self.buffer
+  }
+
+  fn set_ptr(&mut self, pos: i128) -> Result<u64, Error> {
+    if pos < 0 || pos > usize::MAX as i128 {
+      return Err(
+        BinError::SeekOutOfRange {
+          position: pos,
+          length: self.buffer.len() as u64,
+        }
+        .into(),
+      );
+    }
+    self.ptr = pos as usize;
+    Ok(self.ptr as u64)
+  }
+}
+
+impl Default for BytesWriter {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// The writer starts at offset 0, so `buffer` is overwritten from its head.
+impl From<Vec<u8>> for BytesWriter {
+  fn from(buffer: Vec<u8>) -> Self {
+    Self {
+      buffer,
+      ptr: 0,
+      endian: crate::system_endian(),
+      placeholders: Placeholders::new(),
+    }
+  }
+}
+
+impl From<BytesWriter> for Vec<u8> {
+  fn from(writer: BytesWriter) -> Self {
+    writer.buffer
+  }
+}
+
+impl BinaryWriter for BytesWriter {
+  fn set_endian(&mut self, endian: Endian) {
+    self.endian = endian;
+  }
+
+  fn endian(&self) -> Endian {
+    self.endian
+  }
+
+  fn write_bytes(&mut self, array: &[u8]) -> Result<(), Error> {
+    let end = match self.ptr.checked_add(array.len()) {
+      Some(end) => end,
+      None => {
+        let message = format!("{} bytes at {} exceed usize", array.len(), self.ptr);
+        let offset = self.ptr as u64;
+        return Err(BinError::Overflow { offset, message }.into());
+      }
+    };
+    if end > self.buffer.len() {
+      self
+        .buffer
+        .try_reserve(end - self.buffer.len())
+        .map_err(|e| Error::new(ErrorKind::OutOfMemory, e))?;
+      self.buffer.resize(end, 0);
+    }
+    self.buffer[self.ptr..end].copy_from_slice(array);
+    self.ptr = end;
+    Ok(())
+  }
+
+  fn offset(&mut self) -> Result<u64, Error> {
+    Ok(self.ptr as u64)
+  }
+
+  fn seek(&mut self, seek: SeekFrom) -> Result<u64, Error> {
+    match seek {
+      SeekFrom::Start(pos) => self.set_ptr(pos as i128),
+      SeekFrom::End(pos) => self.set_ptr(self.buffer.len() as i128 + pos as i128),
+      SeekFrom::Current(pos) => self.set_ptr(self.ptr as i128 + pos as i128),
+    }
+  }
+
+  fn placeholders(&mut self) -> &mut Placeholders {
+    &mut self.placeholders
+  }
+}
+
+impl Write for BytesWriter {
+  fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+    self.write_bytes(buf)?;
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> Result<(), Error> {
+    Ok(())
+  }
+}
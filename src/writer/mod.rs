@@ -0,0 +1,52 @@
+//! A writer default uses system endianness
+//! If you will use another endianness,use set_endian.
+//! 0.0.11 BinaryWriter is the counterpart of BinaryReader
+//! 0.0.11 write_utf16_string_with, write_local_string (feature="codec")
+//! 0.0.11 write_fixed_string
+
+mod binary;
+mod bit;
+mod bytes;
+mod placeholder;
+mod stream;
+mod to_binary;
+#[cfg(all(any(feature = "async", feature = "futures"), feature = "codec"))]
+pub(crate) use self::binary::local_bytes;
+pub use self::binary::BinaryWriter;
+#[cfg(any(feature = "async", feature = "futures"))]
+pub(crate) use self::binary::{ascii_bytes, fixed_bytes, unmappable, utf16_bytes};
+pub use self::bit::BitWriter;
+pub use self::bytes::BytesWriter;
+pub use self::placeholder::{Placeholder, Placeholders};
+pub use self::stream::StreamWriter;
+pub use self::to_binary::ToBinary;
+
+/// EncodeOptions is options of `write_utf16_string_with` and `write_local_string_with`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EncodeOptions {
+  /// Writes a byte order mark first. Only UTF-8 and UTF-16 have it, others ignore it.
+  pub bom: bool,
+  /// Writes NUL at the end, 0x00 or 0x0000 for UTF-16.
+  pub nul_terminated: bool,
+  /// What to do with characters which the encoding can not represent.
+  pub unmappable: Unmappable,
+}
+
+/// Unmappable is the policy for characters which the encoding can not represent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Unmappable {
+  /// Returns `BinError::UnmappableCharacter`.
+  #[default]
+  Error,
+  /// Writes '?' instead.
+  Replace,
+}
+
+/// Padding is the filler of `write_fixed_string`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Padding {
+  /// 0x00, or 0x0000 for UTF-16.
+  Nul,
+  /// ' ' in the encoding.
+  Space,
+}
@@ -0,0 +1,130 @@
+use crate::error::BinError;
+use crate::Endian;
+use std::io::Error;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Placeholder is a handle of a number field reserved by `reserve_u16`,
+/// `reserve_u32` or `reserve_u64`.
+///
+/// It is passed to `fill`, `fill_length` or `fill_offset`, and stays pending until it is filled.
+#[derive(Debug, PartialEq)]
+pub struct Placeholder {
+  id: u64,
+  offset: u64,
+  size: usize,
+  endian: Endian,
+}
+
+impl Placeholder {
+  /// offset is the absolute offset of the reserved field.
+  pub fn offset(&self) -> u64 {
+    self.offset
+  }
+
+  /// size is the field size in bytes.
+  pub fn size(&self) -> usize {
+    self.size
+  }
+
+  pub fn endian(&self) -> Endian {
+    self.endian
+  }
+
+  /// end is the offset just after the field. `fill_length` counts from here.
+  pub fn end(&self) -> u64 {
+    self.offset + self.size as u64
+  }
+
+  pub(crate) fn to_bytes(&self, value: u64) -> Result<Vec<u8>, Error> {
+    let max = match self.size {
+      2 => u16::MAX as u64,
+      4 => u32::MAX as u64,
+      _ => u64::MAX,
+    };
+    if value > max {
+      let message = format!("placeholder is {} bytes, but value is {}", self.size, value);
+      return Err(
+        BinError::Overflow {
+          offset: self.offset,
+          message,
+        }
+        .into(),
+      );
+    }
+    let bytes = match self.endian {
+      Endian::BigEndian => value.to_be_bytes()[8 - self.size..].to_vec(),
+      Endian::LittleEndian => value.to_le_bytes()[..self.size].to_vec(),
+    };
+    Ok(bytes)
+  }
+}
+
+/// Placeholders keeps the placeholders which are not filled yet.
+///
+/// A `BinaryWriter` implementation owns one and returns it from `placeholders()`.
+#[derive(Debug, Default, Clone)]
+pub struct Placeholders {
+  // (id, offset)
+  pending: Vec<(u64, u64)>,
+}
+
+impl Placeholders {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// len returns the number of unfilled placeholders.
+  pub fn len(&self) -> usize {
+    self.pending.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.pending.is_empty()
+  }
+
+  pub(crate) fn reserve(&mut self, offset: u64, size: usize, endian: Endian) -> Placeholder {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    self.pending.push((id, offset));
+    Placeholder {
+      id,
+      offset,
+      size,
+      endian,
+    }
+  }
+
+  // reserved returns the index of `placeholder`, or an error if this writer did not reserve it.
+  pub(crate) fn reserved(&self, placeholder: &Placeholder) -> Result<usize, Error> {
+    match self
+      .pending
+      .iter()
+      .position(|(id, _)| *id == placeholder.id)
+    {
+      Some(i) => Ok(i),
+      None => {
+        let s = format!(
+          "placeholder at {} is not reserved by this writer",
+          placeholder.offset
+        );
+        Err(BinError::InvalidInput(s).into())
+      }
+    }
+  }
+
+  pub(crate) fn remove(&mut self, placeholder: &Placeholder) -> Result<(), Error> {
+    let i = self.reserved(placeholder)?;
+    self.pending.remove(i);
+    Ok(())
+  }
+
+  pub(crate) fn check(&self) -> Result<(), Error> {
+    if self.pending.is_empty() {
+      Ok(())
+    } else {
+      let offsets = self.pending.iter().map(|(_, offset)| *offset).collect();
+      Err(BinError::UnfilledPlaceholders { offsets }.into())
+    }
+  }
+}
@@ -0,0 +1,100 @@
+use crate::Endian;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Error;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+
+use std::path::PathBuf;
+
+use super::{BinaryWriter, Placeholders};
+
+/// StreamWriter from creating Write
+/// use Write and Seek trait
+#[derive(Debug)]
+pub struct StreamWriter<W> {
+  writer: W,
+  endian: Endian,
+  placeholders: Placeholders,
+}
+
+impl StreamWriter<BufWriter<File>> {
+  /// create_file creates a new file, or truncates an existing file.
+  #[cfg(not(target_family = "wasm"))]
+  pub fn create_file(filename: PathBuf) -> Result<Self, Error> {
+    let file = File::create(filename)?;
+    Ok(Self::from(file))
+  }
+}
+
+impl<W: Write + Seek> StreamWriter<W> {
+  pub fn new(writer: W) -> StreamWriter<W> {
+    StreamWriter {
+      writer,
+      endian: crate::system_endian(),
+      placeholders: Placeholders::new(),
+    }
+  }
+
+  pub fn get_ref(&self) -> &W {
+    &self.writer
+  }
+
+  /// into_inner returns the inner writer. It does not flush.
+  pub fn into_inner(self) -> W {
+    self.writer
+  }
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl From<File> for StreamWriter<BufWriter<File>> {
+  fn from(file: File) -> Self {
+    let writer = BufWriter::new(file);
+    Self {
+      writer,
+      endian: crate::system_endian(),
+      placeholders: Placeholders::new(),
+    }
+  }
+}
+
+impl<W: Write + Seek> BinaryWriter for StreamWriter<W> {
+  fn set_endian(&mut self, endian: Endian) {
+    self.endian = endian;
+  }
+
+  fn endian(&self) -> Endian {
+    self.endian
+  }
+
+  fn write_bytes(&mut self, array: &[u8]) -> Result<(), Error> {
+    self.writer.write_all(array)
+  }
+
+  fn offset(&mut self) -> Result<u64, Error> {
+    self.writer.stream_position()
+  }
+
+  fn seek(&mut self, seek: SeekFrom) -> Result<u64, Error> {
+    self.writer.seek(seek)
+  }
+
+  fn flush(&mut self) -> Result<(), Error> {
+    Write::flush(&mut self.writer)
+  }
+
+  fn placeholders(&mut self) -> &mut Placeholders {
+    &mut self.placeholders
+  }
+}
+
+impl<W: Write + Seek> Write for StreamWriter<W> {
+  fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+    self.writer.write(buf)
+  }
+
+  fn flush(&mut self) -> Result<(), Error> {
+    self.writer.flush()
+  }
+}
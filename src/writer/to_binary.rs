@@ -0,0 +1,99 @@
+use crate::Endian;
+use std::io::Error;
+
+use super::BinaryWriter;
+
+/// ToBinary is a type which can be written by `BinaryWriter::write_value`,
+/// the counterpart of `FromBinary`.
+///
+/// It is implemented for numbers, `bool`, arrays `[T; N]`, tuples
+/// and `Option<T>` tagged by a leading byte (0 = None, 1 = Some).
+pub trait ToBinary {
+  fn write_to<W: BinaryWriter + ?Sized>(&self, writer: &mut W, endian: Endian)
+    -> Result<(), Error>;
+}
+
+macro_rules! impl_to_binary {
+  ($t:ty, $be:ident, $le:ident) => {
+    impl ToBinary for $t {
+      fn write_to<W: BinaryWriter + ?Sized>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+      ) -> Result<(), Error> {
+        match endian {
+          Endian::BigEndian => writer.$be(*self),
+          Endian::LittleEndian => writer.$le(*self),
+        }
+      }
+    }
+  };
+}
+
+impl_to_binary!(u8, write_u8, write_u8);
+impl_to_binary!(i8, write_i8, write_i8);
+impl_to_binary!(u16, write_u16_be, write_u16_le);
+impl_to_binary!(u32, write_u32_be, write_u32_le);
+impl_to_binary!(u64, write_u64_be, write_u64_le);
+impl_to_binary!(u128, write_u128_be, write_u128_le);
+impl_to_binary!(i16, write_i16_be, write_i16_le);
+impl_to_binary!(i32, write_i32_be, write_i32_le);
+impl_to_binary!(i64, write_i64_be, write_i64_le);
+impl_to_binary!(i128, write_i128_be, write_i128_le);
+impl_to_binary!(f32, write_f32_be, write_f32_le);
+impl_to_binary!(f64, write_f64_be, write_f64_le);
+
+impl ToBinary for bool {
+  fn write_to<W: BinaryWriter + ?Sized>(&self, writer: &mut W, _: Endian) -> Result<(), Error> {
+    writer.write_u8(*self as u8)
+  }
+}
+
+impl<T: ToBinary, const N: usize> ToBinary for [T; N] {
+  fn write_to<W: BinaryWriter + ?Sized>(
+    &self,
+    writer: &mut W,
+    endian: Endian,
+  ) -> Result<(), Error> {
+    for value in self.iter() {
+      value.write_to(writer, endian)?;
+    }
+    Ok(())
+  }
+}
+
+impl<T: ToBinary> ToBinary for Option<T> {
+  fn write_to<W: BinaryWriter + ?Sized>(
+    &self,
+    writer: &mut W,
+    endian: Endian,
+  ) -> Result<(), Error> {
+    match self {
+      None => writer.write_u8(0),
+      Some(value) => {
+        writer.write_u8(1)?;
+        value.write_to(writer, endian)
+      }
+    }
+  }
+}
+
+macro_rules! impl_to_binary_tuple {
+  ($($t:ident $i:tt),+) => {
+    impl<$($t: ToBinary),+> ToBinary for ($($t,)+) {
+      fn write_to<W: BinaryWriter + ?Sized>(&self, writer: &mut W, endian: Endian) -> Result<(), Error> {
+        $(self.$i.write_to(writer, endian)?;)+
+        Ok(())
+      }
+    }
+  };
+}
+
+impl_to_binary_tuple!(A 0);
+impl_to_binary_tuple!(A 0, B 1);
+impl_to_binary_tuple!(A 0, B 1, C 2);
+impl_to_binary_tuple!(A 0, B 1, C 2, D 3);
+impl_to_binary_tuple!(A 0, B 1, C 2, D 3, E 4);
+impl_to_binary_tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_to_binary_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_to_binary_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);